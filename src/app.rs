@@ -7,7 +7,7 @@ use leptos_router::{
 };
 
 use crate::{
-    areas::{areas_context::{AreaContextProvider, AreaRoute}, views::areas_table::AreasTable}, 
+    areas::{areas_context::{AreaContextProvider, AreaRoute}, views::{areas_page::AreasPage, areas_table::AreasTable}}, 
     catalog::catalog_context::{CatalogContextProvider, CatalogRoute}, 
     content::content_context::{ProjectContentContextProvider, ProjectContentRoute}, 
     pages::{about_page::AboutPage, editor_page::EditorPage, home_page::HomePage}, 
@@ -15,11 +15,20 @@ use crate::{
     auth::{
         auth_context::AuthProvider,
         views::{
+            auth_callback::AuthCallback,
             auth_form::{AuthForm, AuthFormMode},
             reset_password_form::ResetPasswordForm,
+            update_password_form::UpdatePasswordForm,
+            users_page::UsersPage,
+            profile_page::ProfilePage,
             protected_route::ProtectedRoute,
-        }
-    }
+        },
+        permissions::Permission,
+    },
+    backend::{outbox::OutboxSync, realtime::RealtimeSync, BackendProvider},
+    shared::environment::Environment,
+    supabase::SupabaseProvider,
+    ui::error::ErrorMessage,
 };
 
 #[component]
@@ -37,18 +46,42 @@ fn RedirectToHome() -> impl IntoView {
 
 
 
+/// Loads the runtime environment before anything talks to Supabase.
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
 
+    let environment = LocalResource::new(Environment::load);
+
     view! {
         <Stylesheet id="leptos" href="/style/output.css"/>
         <Link rel="icon" type_="image/png" href="/public/favicon.png" />
+        {move || environment.get().map(|env| match env {
+            Ok(env) => view! { <AppShell environment=env /> }.into_any(),
+            Err(err) => view! {
+                <div class="min-h-screen flex items-center justify-center bg-gray-50 px-4">
+                    <ErrorMessage message=err />
+                </div>
+            }.into_any(),
+        })}
+    }
+}
+
+#[component]
+fn AppShell(environment: Environment) -> impl IntoView {
+    let supabase = environment.supabase.clone();
+    provide_context(environment);
+
+    view! {
+        <SupabaseProvider config=supabase>
         <AuthProvider>
+        <BackendProvider>
         <CatalogContextProvider>
         <AreaContextProvider>
         <ProjectProvider> 
         <ProjectContentContextProvider> 
+        <RealtimeSync>
+        <OutboxSync>
             <Router>
                 <Routes fallback=|| "Page not found.">
                  // Auth routes (public)
@@ -70,8 +103,24 @@ pub fn App() -> impl IntoView {
                      </div>
                  }/>
                  
+                 <Route path=path!("/auth/callback") view=|| view! {
+                     <div class="min-h-screen flex items-center justify-center bg-gray-50 py-12 px-4 sm:px-6 lg:px-8">
+                         <AuthCallback />
+                     </div>
+                 }/>
+                 
+                 <Route path=path!("/update-password") view=|| view! {
+                     <div class="min-h-screen flex items-center justify-center bg-gray-50 py-12 px-4 sm:px-6 lg:px-8">
+                         <UpdatePasswordForm />
+                     </div>
+                 }/>
+                 
                  // Profile route (protected)
-             
+                 <Route path=path!("/profile") view=|| view! {
+                     <ProtectedRoute redirect_path="/login">
+                         <ProfilePage />
+                     </ProtectedRoute>
+                 }/>
                  
                  <Route path=path!("") view=RedirectToHome/>
                  
@@ -106,7 +155,7 @@ pub fn App() -> impl IntoView {
                  <ParentRoute 
                         path=StaticSegment("/editor") 
                         view=||{ view! {
-                            <ProtectedRoute redirect_path="/login" >
+                            <ProtectedRoute redirect_path="/login" permission=Permission::ViewEditor require_aal2=true>
                             <AreaRoute>
                             <CatalogRoute>
                              <ProjectRoute>
@@ -118,6 +167,14 @@ pub fn App() -> impl IntoView {
                         }}
                     >
                     
+                        <Route path=path!("areas") view=AreasPage/>
+
+                        <Route path=path!("users") view=|| view! {
+                            <ProtectedRoute permission=Permission::ManageUsers>
+                                <UsersPage />
+                            </ProtectedRoute>
+                        }/>
+
                         <Route path=path!(":project_id")   
                         view=||{ 
                             
@@ -137,11 +194,15 @@ pub fn App() -> impl IntoView {
                     </ParentRoute>
                 </Routes>   
             </Router>
+        </OutboxSync>
+        </RealtimeSync>
         </ProjectContentContextProvider>   
         </ProjectProvider>   
         </AreaContextProvider>  
         </CatalogContextProvider>  
+        </BackendProvider>
         </AuthProvider>
+        </SupabaseProvider>
     }
 }
 
@@ -1,5 +1,7 @@
-use crate::areas::model::{ProjectArea};
-use crate::supabase::{supabase_get, supabase_post, supabase_patch, supabase_delete};
+use crate::areas::model::{ProjectArea, ProjectAreaDto};
+use crate::backend::{for_each_page, use_backend, Backend, Resource};
+use crate::shared::{ordering::{renumber, replace_by_id}, task::{spawn_cancellable, Loading, LoadingGuard}};
+use crate::supabase::{realtime::Change, SupabaseError};
 use leptos::{
     logging,
     prelude::{
@@ -12,11 +14,10 @@ use leptos::{
         Update,
         WriteSignal,
     },
-    task::spawn_local,
     *,
 };
 use std::sync::Arc;
-use leptos::prelude::Get;
+use leptos::prelude::{Get, GetUntracked};
 
 
 
@@ -25,38 +26,49 @@ pub struct AreaContext {
     pub areas: (ReadSignal<Vec<ProjectArea>>, WriteSignal<Vec<ProjectArea>>),
     pub categories: (ReadSignal<Vec<String>>, WriteSignal<Vec<String>>),
     pub default_category: (ReadSignal<Option<String>>, WriteSignal<Option<String>>),
-    pub is_loading: (ReadSignal<bool>, WriteSignal<bool>),
-    pub error: (ReadSignal<Option<String>>, WriteSignal<Option<String>>),
-    url_path: String,
+    /// Number of areas on the server, known once the first page has loaded.
+    pub total_count: (ReadSignal<Option<usize>>, WriteSignal<Option<usize>>),
+    pub is_loading: Loading,
+    pub error: (ReadSignal<Option<SupabaseError>>, WriteSignal<Option<SupabaseError>>),
+    backend: Arc<dyn Backend>,
 }
 
 impl AreaContext {
-    pub fn new() -> Self {
+    pub fn new(backend: Arc<dyn Backend>) -> Self {
         Self {
             areas: signal::<Vec<ProjectArea>>(vec![]),
             categories: signal::<Vec<String>>(vec![]),
             default_category: signal(None),
-            is_loading: signal(false),
+            total_count: signal(None),
+            is_loading: Loading::default(),
             error: signal(None),
-            url_path: "/rest/v1/areas_view?select=*".to_string(),
+            backend,
         }
     }
 
     pub async fn fetch_areas(&self) {
-        self.is_loading.1.try_update(|v| {
-            *v = true;
-        });
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| {
             *e = None;
         });
-        match supabase_get::<Vec<ProjectArea>>(&self.url_path).await {
-            Ok(items) => {
-                logging::log!("Fetched areas successfully: {:?}", items);
-                self.parse_categories(items.clone());
-                self.areas.1.set(items);
+        let mut loaded = vec![];
+        let result = for_each_page(
+            |offset, limit| self.backend.fetch_areas(offset, limit),
+            |page| {
+                self.total_count.1.set(page.total);
+                self.areas.1.update(|items| page.merge_into(items, |a| a.id));
+                loaded.extend(page.items);
+            },
+        ).await;
+        match result {
+            Ok(_) => {
+                logging::log!("Fetched {} areas successfully", loaded.len());
+                self.parse_categories(loaded.clone());
+                self.areas.1.set(loaded);
             }
             Err(err) => {
                 logging::log!("Error fetching areas: {}", err);
+                self.error.1.set(Some(err));
             }
         }
     }
@@ -75,20 +87,23 @@ impl AreaContext {
     }
 
     pub async fn add_area(&self, title: String, category: String, desc: Option<String>) {
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
         
-        let new_area = serde_json::json!({
-            "title": title,
-            "category": category,
-            "desc": desc
-        });
+        let new_area = ProjectAreaDto {
+            title,
+            category,
+            desc,
+            ..ProjectAreaDto::default()
+        };
         
-        match supabase_post::<ProjectArea, serde_json::Value>("/rest/v1/areas", &new_area).await {
+        match self.backend.create_area(new_area).await {
             Ok(area) => {
+                self.backend.invalidate(Resource::Areas);
                 self.areas.1.update(|areas| {
                     areas.push(area);
                 });
+                self.total_count.1.update(|total| *total = total.map(|t| t + 1));
                 // Refresh categories
                 let current_areas = self.areas.0.get();
                 self.parse_categories(current_areas);
@@ -98,27 +113,23 @@ impl AreaContext {
                 self.error.1.set(Some(err));
             }
         }
-        
-        self.is_loading.1.try_update(|v| *v = false);
     }
 
     pub async fn create_area(&self, area: ProjectArea) {
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
         
-        let new_area = serde_json::json!({
-            "title": area.title,
-            "category": area.category,
-            "desc": area.desc
-        });
+        let new_area = area.to_dto();
 
         logging::log!("Creating area with data: {:?}", new_area);   
         
-        match supabase_post::<ProjectArea, serde_json::Value>("/rest/v1/areas", &new_area).await {
+        match self.backend.create_area(new_area).await {
             Ok(area) => {
+                self.backend.invalidate(Resource::Areas);
                 self.areas.1.update(|areas| {
                     areas.push(area);
                 });
+                self.total_count.1.update(|total| *total = total.map(|t| t + 1));
                 // Refresh categories
                 let current_areas = self.areas.0.get();
                 self.parse_categories(current_areas);
@@ -128,25 +139,18 @@ impl AreaContext {
                 self.error.1.set(Some(err));
             }
         }
-        
-        self.is_loading.1.try_update(|v| *v = false);
     }
 
     pub async fn update_area(&self, area: ProjectArea) {
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
         
-        let updated_area = serde_json::json!({
-            "title": area.title,
-            "category": area.category,
-            "desc": area.desc,
-            "order": area.order
-        });
-        
-        match supabase_patch::<ProjectArea, serde_json::Value>(&format!("/rest/v1/areas?id=eq.{}", area.id), &updated_area).await {
+        let area_id = area.id;
+        match self.backend.update_area(area).await {
             Ok(updated) => {
+                self.backend.invalidate(Resource::Areas);
                 self.areas.1.update(|areas| {
-                    if let Some(pos) = areas.iter().position(|a| a.id == area.id) {
+                    if let Some(pos) = areas.iter().position(|a| a.id == area_id) {
                         areas[pos] = updated;
                     }
                 });
@@ -159,19 +163,20 @@ impl AreaContext {
                 self.error.1.set(Some(err));
             }
         }
-        
-        self.is_loading.1.try_update(|v| *v = false);
     }
 
     pub async fn delete_area(&self, area_id: i64) {
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
         
-        match supabase_delete(&format!("/rest/v1/areas?id=eq.{}", area_id)).await {
+        match self.backend.delete_area(area_id).await {
             Ok(_) => {
+                self.backend.invalidate(Resource::Areas);
+                self.backend.invalidate(Resource::Catalog);
                 self.areas.1.update(|areas| {
                     areas.retain(|area| area.id != area_id);
                 });
+                self.total_count.1.update(|total| *total = total.map(|t| t.saturating_sub(1)));
                 // Refresh categories
                 let current_areas = self.areas.0.get();
                 self.parse_categories(current_areas);
@@ -181,8 +186,94 @@ impl AreaContext {
                 self.error.1.set(Some(err));
             }
         }
-        
-        self.is_loading.1.try_update(|v| *v = false);
+    }
+
+    /// Number the areas 1..n in the given order, writing the changed rows in one request.
+    pub async fn renumber_areas(&self, ordered_ids: Vec<i64>) {
+        let changed = renumber(&self.areas.0.get_untracked(), &ordered_ids, |a| a.id, |a| &mut a.order);
+        self.save_areas(changed).await;
+    }
+
+    /// Move areas to another category, after the areas already in it.
+    pub async fn move_areas_to_category(&self, area_ids: Vec<i64>, category: String) {
+        let areas = self.areas.0.get_untracked();
+        let last_order = areas.iter()
+            .filter(|a| a.category == category && !area_ids.contains(&a.id))
+            .filter_map(|a| a.order)
+            .max()
+            .unwrap_or(0);
+        let moved = areas.into_iter()
+            .filter(|a| area_ids.contains(&a.id) && a.category != category)
+            .enumerate()
+            .map(|(i, area)| ProjectArea {
+                category: category.clone(),
+                order: Some(last_order + i as i32 + 1),
+                ..area
+            })
+            .collect();
+        self.save_areas(moved).await;
+    }
+
+    async fn save_areas(&self, areas: Vec<ProjectArea>) {
+        if areas.is_empty() {
+            return;
+        }
+        let _loading = LoadingGuard::new(self.is_loading);
+        self.error.1.set(None);
+
+        match self.backend.upsert_areas(areas).await {
+            Ok(written) => {
+                self.backend.invalidate(Resource::Areas);
+                self.areas.1.update(|areas| replace_by_id(areas, &written, |a| a.id));
+                self.parse_categories(self.areas.0.get_untracked());
+            }
+            Err(err) => {
+                logging::log!("Error saving areas: {}", err);
+                self.error.1.set(Some(err));
+            }
+        }
+    }
+
+    /// Delete the selected areas, and their catalog links, in one request.
+    pub async fn delete_areas(&self, area_ids: Vec<i64>) {
+        if area_ids.is_empty() {
+            return;
+        }
+        let _loading = LoadingGuard::new(self.is_loading);
+        self.error.1.set(None);
+
+        match self.backend.delete_areas(area_ids.clone()).await {
+            Ok(_) => {
+                self.backend.invalidate(Resource::Areas);
+                self.backend.invalidate(Resource::Catalog);
+                let mut removed = 0;
+                self.areas.1.update(|areas| {
+                    let before = areas.len();
+                    areas.retain(|area| !area_ids.contains(&area.id));
+                    removed = before - areas.len();
+                });
+                self.total_count.1.update(|total| *total = total.map(|t| t.saturating_sub(removed)));
+                self.parse_categories(self.areas.0.get_untracked());
+            }
+            Err(err) => {
+                logging::log!("Error deleting areas: {}", err);
+                self.error.1.set(Some(err));
+            }
+        }
+    }
+
+    /// Apply a change made elsewhere, received over Realtime.
+    pub fn apply_change(&self, change: &Change) {
+        let mut delta = 0;
+        self.areas.1.update(|areas| {
+            let mut change = change.clone();
+            if let Some(area) = ProjectArea::from_table_row(&change.record, areas) {
+                change.record = serde_json::to_value(area).unwrap_or_default();
+            }
+            delta = change.apply(areas, |a| a.id);
+        });
+        self.total_count.1.update(|total| *total = total.map(|t| (t as i64 + delta).max(0) as usize));
+        self.parse_categories(self.areas.0.get());
     }
 
     pub fn get_areas_by_category(&self, category: &String) -> Vec<ProjectArea> {
@@ -202,7 +293,7 @@ impl AreaContext {
 
 #[component]
 pub fn AreaContextProvider(children: Children) -> impl IntoView {
-    let editor_context = Arc::new(AreaContext::new());
+    let editor_context = Arc::new(AreaContext::new(use_backend()));
    
     provide_context(editor_context);
 
@@ -212,7 +303,7 @@ pub fn AreaContextProvider(children: Children) -> impl IntoView {
 #[component]
 pub fn AreaRoute(children: Children) -> impl IntoView {
     let area_context = use_areas();
-    spawn_local(async move {
+    spawn_cancellable(async move {
         area_context.fetch_areas().await;
     });
 
@@ -225,3 +316,33 @@ pub fn use_areas() -> Arc<AreaContext> {
         "AreaContext not found. Make sure you are using AreaContextProvider."
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::InMemoryBackend;
+    use futures::executor::block_on;
+
+    #[test]
+    fn fetch_areas_collects_sorted_categories() {
+        let context = AreaContext::new(Arc::new(InMemoryBackend::seeded()));
+        block_on(context.fetch_areas());
+
+        let categories = context.categories.0.get();
+        assert_eq!(categories, vec!["Industry", "Maturity", "Technologies", "Tools"]);
+        assert_eq!(context.default_category.0.get(), Some("Industry".to_string()));
+        assert_eq!(context.total_count.0.get(), Some(context.areas.0.get().len()));
+        assert!(context.error.0.get().is_none());
+    }
+
+    #[test]
+    fn deleting_last_area_of_category_drops_category() {
+        let context = AreaContext::new(Arc::new(InMemoryBackend::default()));
+        block_on(context.add_area("10".to_string(), "Scale".to_string(), None));
+        let area_id = context.areas.0.get()[0].id;
+
+        block_on(context.delete_area(area_id));
+        assert!(context.areas.0.get().is_empty());
+        assert!(context.categories.0.get().is_empty());
+    }
+}
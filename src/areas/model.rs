@@ -25,6 +25,20 @@ pub struct ProjectArea {
 
 
 impl ProjectArea {
+    /// An area from a row of the `areas` table, as Realtime sends it. The list is loaded
+    /// from `areas_view`, which adds the `format`, so a row without one keeps the format
+    /// of the loaded area, or of another area in the same category.
+    pub fn from_table_row(record: &serde_json::Value, loaded: &[ProjectArea]) -> Option<Self> {
+        let mut area: ProjectArea = serde_json::from_value(record.clone()).ok()?;
+        if record.get("format").is_none() {
+            area.format = loaded.iter()
+                .find(|a| a.id == area.id && a.category == area.category)
+                .or_else(|| loaded.iter().find(|a| a.category == area.category))
+                .and_then(|a| a.format.clone());
+        }
+        Some(area)
+    }
+
     pub fn to_dto(&self) -> ProjectAreaDto {
         ProjectAreaDto {
             title: self.title.clone(),
@@ -78,3 +92,39 @@ impl ProjectAreaDto {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(id: i64, category: &str, format: Option<FormatTypes>) -> ProjectArea {
+        ProjectArea {
+            id,
+            created_at: None,
+            title: "-3".to_string(),
+            category: category.to_string(),
+            desc: None,
+            order: Some(1),
+            format,
+        }
+    }
+
+    #[test]
+    fn table_rows_keep_the_format_of_their_category() {
+        let loaded = vec![area(1, "Precision", Some(FormatTypes::Exponential)), area(2, "Tools", None)];
+        let row = serde_json::json!({
+            "id": 3,
+            "created_at": "2026-10-18T12:00:00Z",
+            "title": "-6",
+            "category": "Precision",
+            "desc": null,
+            "order": 2,
+        });
+
+        let decoded = ProjectArea::from_table_row(&row, &loaded).unwrap();
+        assert_eq!(decoded.title, "-6");
+        assert_eq!(decoded.format, Some(FormatTypes::Exponential));
+
+        let moved = serde_json::json!({ "id": 1, "title": "mobile", "category": "Tools" });
+        assert_eq!(ProjectArea::from_table_row(&moved, &loaded).unwrap().format, None);
+    }
+}
@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use leptos::{logging, prelude::*, reactive::spawn_local};
 
-use crate::{areas::{areas_context::use_areas, model::ProjectArea}, catalog::catalog_context::use_catalog, shared::data_state_model::DataState, ui::{button::{ButtonSize, CancelButton, DangerButton}, form::{form_input_field::InputField, form_text_area::FormTextArea}}};
+use crate::{areas::{areas_context::use_areas, model::ProjectArea}, auth::{auth_context::use_auth, permissions::Permission}, catalog::catalog_context::use_catalog, shared::data_state_model::DataState, ui::{button::{ButtonSize, CancelButton, DangerButton}, error::ErrorMessage, form::{form_input_field::InputField, form_text_area::FormTextArea}}};
 
 
 
@@ -125,6 +125,7 @@ pub fn AreaForm(
     let catalog_context = use_catalog();
     let areas_context = use_areas();
     let areas_context_clone = areas_context.clone();
+    let can_delete = use_auth().can(Permission::Delete);
 
     let area_clone = area.clone();
 
@@ -193,11 +194,18 @@ let handle_update_area = {
         }
     };
 
+    let area_error = {
+        let areas_context = areas_context.clone();
+        move || areas_context.error.0.get()
+            .map(|err| view! { <ErrorMessage message=err.to_string() /> })
+    };
+
     let handle_create_area_clone = Arc::new(handle_create_area.clone());
     let handle_update_area_clone = Arc::new(handle_update_area.clone());
 let mut on_close = on_close.clone();
     view! {
         <div class="">
+            {area_error}
             <div class="w-full flex flex-col space-y-4">
             {
                 if area_clone.is_some() {
@@ -240,7 +248,7 @@ let mut on_close = on_close.clone();
                 <div class="flex justify-between mt-1">
                     {
                         if let Some(area) = area_clone {
-                            if area.id > 0 {
+                            if area.id > 0 && can_delete {
                                 view!{
                                      <DangerButton
                                         size=ButtonSize::Small
@@ -3,13 +3,18 @@ use leptos::task::spawn_local;
 
 use crate::areas::areas_context::use_areas;
 use crate::areas::model::ProjectArea;
+use crate::auth::{auth_context::use_auth, permissions::Permission};
 use crate::ui::*;
+use crate::ui::checkbox::Checkbox;
 
 #[component]
 pub fn AreasList(
     #[prop(into)] on_edit: Callback<ProjectArea>,
 ) -> impl IntoView {
     let area_context = use_areas();
+    let auth = use_auth();
+    let can_edit = auth.can(Permission::Edit);
+    let can_delete = auth.can(Permission::Delete);
     
     // Handle delete
     let handle_delete = {
@@ -28,15 +33,113 @@ pub fn AreasList(
         }
     };
 
+    // Areas ticked for a bulk action, and the category "Move to" targets
+    let checked_ids = RwSignal::new(Vec::<i64>::new());
+    let target_category = RwSignal::new(String::new());
+
+    let handle_move_checked = {
+        let area_context = area_context.clone();
+        move |_| {
+            let category = target_category.get_untracked();
+            if category.is_empty() {
+                return;
+            }
+            let ids = checked_ids.get_untracked();
+            let area_context = area_context.clone();
+            spawn_local(async move {
+                area_context.move_areas_to_category(ids, category).await;
+                checked_ids.set(vec![]);
+            });
+        }
+    };
+
+    let handle_delete_checked = {
+        let area_context = area_context.clone();
+        move |_| {
+            let ids = checked_ids.get_untracked();
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message(&format!("Delete {} area(s)?", ids.len())).ok())
+                .unwrap_or(false);
+            if confirmed {
+                let area_context = area_context.clone();
+                spawn_local(async move {
+                    area_context.delete_areas(ids).await;
+                    checked_ids.set(vec![]);
+                });
+            }
+        }
+    };
+
+    // Number a category's areas 1..n in the order they are listed
+    let handle_renumber = {
+        let area_context = area_context.clone();
+        move |category: String| {
+            let mut ordered = area_context.get_areas_by_category(&category);
+            ordered.sort_by_key(|a| (a.order, a.id));
+            let ordered_ids = ordered.iter().map(|a| a.id).collect();
+            let area_context = area_context.clone();
+            spawn_local(async move { area_context.renumber_areas(ordered_ids).await });
+        }
+    };
+
+    let categories = {
+        let area_context = area_context.clone();
+        move || area_context.categories.0.get()
+    };
+
+    // Areas arrive a page at a time; show progress until the list is complete
+    let loading_progress = {
+        let area_context = area_context.clone();
+        move || {
+            let loaded = area_context.areas.0.get().len();
+            area_context.total_count.0.get()
+                .filter(|total| loaded < *total)
+                .map(|total| format!("Loaded {} of {} areas…", loaded, total))
+        }
+    };
+
     view! {
         <div class="bg-white rounded-lg shadow-md p-6">
             <h2 class="text-xl font-semibold mb-4">"Areas List"</h2>
+            {move || loading_progress().map(|progress| view! {
+                <p class="text-sm text-gray-500 mb-4">{progress}</p>
+            })}
+
+            {move || {
+                let count = checked_ids.get().len();
+                let handle_move_checked = handle_move_checked.clone();
+                let handle_delete_checked = handle_delete_checked.clone();
+                let categories = categories.clone();
+                (count > 0).then(move || view! {
+                    <div class="flex gap-2 items-center text-sm mb-4">
+                        <span>{format!("{} selected", count)}</span>
+                        <select
+                            class="border border-gray-300 rounded px-2 py-1"
+                            on:change:target=move |ev| target_category.set(ev.target().value())
+                        >
+                            <option value="">"Move to…"</option>
+                            {categories().into_iter().map(|category| view! {
+                                <option value=category.clone()>{category.clone()}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <SecondaryButton size=ButtonSize::Small on_click=Box::new(handle_move_checked)>
+                            "Move"
+                        </SecondaryButton>
+                        {can_delete.then(|| view! {
+                            <DangerButton size=ButtonSize::Small on_click=Box::new(handle_delete_checked)>
+                                "Delete selected"
+                            </DangerButton>
+                        })}
+                    </div>
+                })
+            }}
             
             <div class="space-y-4 max-h-96 overflow-y-auto">
                 {move || {
                     let areas_list = area_context.areas.0.get();
                     let handle_edit_fn = on_edit.clone();
                     let handle_delete_fn = handle_delete.clone();
+                    let handle_renumber_fn = handle_renumber.clone();
                     
                     // Group areas by category
                     let mut grouped_areas = std::collections::HashMap::new();
@@ -52,13 +155,23 @@ pub fn AreasList(
                     categories.sort();
                     
                     categories.into_iter().map(|category| {
-                        let areas_in_category = grouped_areas.get(&category).unwrap().clone();
+                        let mut areas_in_category = grouped_areas.get(&category).unwrap().clone();
+                        areas_in_category.sort_by_key(|a| (a.order, a.id));
                         let category_name = category.clone();
+                        let renumber_fn = handle_renumber_fn.clone();
                         
                         view! {
                             <div class="mb-6">
-                                <h3 class="text-lg font-semibold text-gray-800 mb-3 border-b border-gray-300 pb-2">
+                                <h3 class="text-lg font-semibold text-gray-800 mb-3 border-b border-gray-300 pb-2 flex justify-between items-center">
                                     {category_name}
+                                    {can_edit.then(|| view! {
+                                        <SecondaryButton
+                                            size=ButtonSize::Small
+                                            on_click=Box::new(move |_| renumber_fn(category.clone()))
+                                        >
+                                            "Renumber"
+                                        </SecondaryButton>
+                                    })}
                                 </h3>
                                 <div class="space-y-3">
                                     {areas_in_category.into_iter().map(|area| {
@@ -72,7 +185,20 @@ pub fn AreasList(
                                         
                                         view! {
                                             <div class="p-4 border border-gray-200 rounded-lg hover:bg-gray-50 transition-colors ml-4">
-                                                <div class="flex justify-between items-start">
+                                                <div class="flex justify-between items-start gap-3">
+                                                    {can_edit.then(|| view! {
+                                                        <Checkbox
+                                                            class="mt-1".to_string()
+                                                            checked=Signal::derive(move || checked_ids.get().contains(&area_id))
+                                                            on_change=Box::new(move |_| checked_ids.update(|ids| {
+                                                                if ids.contains(&area_id) {
+                                                                    ids.retain(|id| *id != area_id);
+                                                                } else {
+                                                                    ids.push(area_id);
+                                                                }
+                                                            }))
+                                                        />
+                                                    })}
                                                     <div class="flex-1">
                                                         <h4 class="font-medium text-gray-900">{area_title.clone()}</h4>
                                                         {area_desc.clone().map(|desc| view! {
@@ -80,18 +206,22 @@ pub fn AreasList(
                                                         })}
                                                     </div>
                                                     <div class="flex gap-2 ml-4">
-                                                        <SecondaryButton
-                                                            size=ButtonSize::Small
-                                                            on_click=Box::new(move |_| edit_fn.run(area_for_edit.clone()))
-                                                        >
-                                                            "Edit"
-                                                        </SecondaryButton>
-                                                        <DangerButton
-                                                            size=ButtonSize::Small
-                                                            on_click=Box::new(move |_| delete_fn(area_id, area_title.clone()))
-                                                        >
-                                                            "Delete"
-                                                        </DangerButton>
+                                                        {can_edit.then(|| view! {
+                                                            <SecondaryButton
+                                                                size=ButtonSize::Small
+                                                                on_click=Box::new(move |_| edit_fn.run(area_for_edit.clone()))
+                                                            >
+                                                                "Edit"
+                                                            </SecondaryButton>
+                                                        })}
+                                                        {can_delete.then(|| view! {
+                                                            <DangerButton
+                                                                size=ButtonSize::Small
+                                                                on_click=Box::new(move |_| delete_fn(area_id, area_title.clone()))
+                                                            >
+                                                                "Delete"
+                                                            </DangerButton>
+                                                        })}
                                                     </div>
                                                 </div>
                                             </div>
@@ -0,0 +1,25 @@
+use leptos::prelude::*;
+
+use crate::areas::{model::ProjectArea, views::{area_form::AreaForm, areas_list::AreasList}};
+
+/// Editor page listing every area, with bulk renumber, move and delete.
+#[component]
+pub fn AreasPage() -> impl IntoView {
+    let editing = RwSignal::new(None::<ProjectArea>);
+
+    view! {
+        <div class="flex flex-col gap-4 p-4 w-full max-w-3xl">
+            {move || editing.get().map(|area| {
+                let category = area.category.clone();
+                view! {
+                    <AreaForm area=area category=category on_close=move |is_open: bool| {
+                        if !is_open {
+                            editing.set(None);
+                        }
+                    } />
+                }
+            })}
+            <AreasList on_edit=move |area: ProjectArea| editing.set(Some(area)) />
+        </div>
+    }
+}
@@ -1,4 +1,5 @@
 pub mod areas_table;
 pub mod areas_list;
+pub mod areas_page;
 pub mod area_editor;
 pub mod area_form;
\ No newline at end of file
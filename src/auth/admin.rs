@@ -0,0 +1,95 @@
+use crate::auth::model::User;
+use crate::auth::permissions::Role;
+use crate::supabase::{SupabaseClient, SupabaseError};
+use gloo_net::http::Method;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Edge function that manages accounts with the service key, which must never
+/// reach the browser. It checks that the caller is an admin at `aal2`.
+/// Source: `supabase/functions/admin-users`.
+const ADMIN_USERS_FUNCTION: &str = "/functions/v1/admin-users";
+
+/// One call to the admin-users function.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AdminRequest {
+    List,
+    Invite { email: String, role: String, redirect_to: Option<String> },
+    SetRole { user_id: String, role: String },
+    SetDisabled { user_id: String, disabled: bool },
+}
+
+impl AdminRequest {
+    /// Invite `email` with `role`; the emailed link lands on `redirect_to` to choose a password.
+    pub fn invite(email: &str, role: Role, redirect_to: Option<String>) -> Result<Self, String> {
+        let email = email.trim();
+        if email.is_empty() || !email.contains('@') {
+            return Err("Enter the email address to invite".to_string());
+        }
+        Ok(AdminRequest::Invite { email: email.to_string(), role: role.as_str().to_string(), redirect_to })
+    }
+}
+
+/// Client for account management, for admins only.
+#[derive(Clone)]
+pub struct UserAdmin {
+    client: SupabaseClient,
+}
+
+impl UserAdmin {
+    pub fn new(client: SupabaseClient) -> Self {
+        Self { client }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, request: &AdminRequest) -> Result<T, SupabaseError> {
+        let body = serde_json::to_string(request).map_err(|e| SupabaseError::Request(e.to_string()))?;
+        let url = self.client.endpoint(ADMIN_USERS_FUNCTION);
+        let resp = self.client.send(Method::POST, &url, &[("Content-Type", "application/json")], Some(&body)).await?;
+        if resp.ok() {
+            resp.json()
+        } else {
+            Err(resp.error("users"))
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<User>, SupabaseError> {
+        self.call(&AdminRequest::List).await
+    }
+
+    pub async fn invite(&self, request: AdminRequest) -> Result<User, SupabaseError> {
+        self.call(&request).await
+    }
+
+    pub async fn set_role(&self, user_id: &str, role: Role) -> Result<User, SupabaseError> {
+        self.call(&AdminRequest::SetRole { user_id: user_id.to_string(), role: role.as_str().to_string() }).await
+    }
+
+    pub async fn set_disabled(&self, user_id: &str, disabled: bool) -> Result<User, SupabaseError> {
+        self.call(&AdminRequest::SetDisabled { user_id: user_id.to_string(), disabled }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_name_their_action() {
+        let request = AdminRequest::SetRole { user_id: "8c1e".to_string(), role: Role::Editor.as_str().to_string() };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"action":"set_role","user_id":"8c1e","role":"editor"}"#
+        );
+        assert_eq!(serde_json::to_string(&AdminRequest::List).unwrap(), r#"{"action":"list"}"#);
+    }
+
+    #[test]
+    fn invites_need_an_email() {
+        assert!(AdminRequest::invite("  ", Role::Editor, None).is_err());
+        assert_eq!(
+            AdminRequest::invite(" ada@example.com ", Role::Editor, None).unwrap(),
+            AdminRequest::Invite { email: "ada@example.com".to_string(), role: "editor".to_string(), redirect_to: None }
+        );
+    }
+}
@@ -1,28 +1,148 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use crate::auth::model::{
     User, Session, AuthResponse, SignInRequest, SignUpRequest, 
-    ResetPasswordRequest, UpdateUserRequest, AuthError, LocalSession
+    ResetPasswordRequest, UpdateUserRequest, UpdatePasswordRequest, RefreshTokenRequest,
+    OtpRequest, VerifyOtpRequest, PkceTokenRequest, UserMetadata, check_avatar,
+    EnrollFactorRequest, EnrolledFactor, FactorChallenge, VerifyFactorRequest
 };
-use crate::supabase::SupabaseConfig;
+use crate::auth::mfa::{token_aal, Aal};
+use crate::auth::pkce::{self, VERIFIER_STORAGE_KEY};
+use crate::auth::fragment::FragmentTokens;
+use crate::auth::local::local_admin_session;
+use crate::auth::permissions::{Permission, Role};
+use crate::shared::environment::{use_environment, BackendKind};
+use crate::supabase::{avatar_path, use_supabase, SupabaseClient, SupabaseError};
+use futures::channel::oneshot;
 use leptos::{
     logging,
     prelude::{
         provide_context,
+        set_timeout_with_handle,
+        TimeoutHandle,
         signal,
         use_context,
+        window,
         Children,
+        Effect,
         ReadSignal,
         Set,
+        Update,
         WriteSignal,
         Get,
+        GetUntracked,
+        With,
     },
+    task::spawn_local,
     *,
 };
-use gloo_net::http::Request;
+use gloo_net::http::Method;
 use gloo_storage::{LocalStorage, Storage};
 use serde_json;
+use leptos_use::use_event_listener;
+use web_sys::{File, StorageEvent};
 
 const AUTH_STORAGE_KEY: &str = "supabase_auth";
-const LOCAL_SESSION_KEY: &str = "local_auth_session";
+/// Older versions kept an unverified 24-hour session here; it is dropped on startup.
+const LEGACY_LOCAL_SESSION_KEY: &str = "local_auth_session";
+/// Public Storage bucket of profile pictures, one folder per user id.
+const AVATARS_BUCKET: &str = "avatars";
+
+/// Where password-recovery emails send the user to choose a new password.
+pub const UPDATE_PASSWORD_PATH: &str = "/update-password";
+
+/// Where magic links send the user to finish signing in.
+pub const AUTH_CALLBACK_PATH: &str = "/auth/callback";
+
+/// Absolute URL of `path` on this site.
+pub(crate) fn site_url(path: &str) -> Option<String> {
+    window().location().origin().ok().map(|origin| format!("{}{}", origin, path))
+}
+
+/// Auth endpoint `path` with a `redirect_to` back to `target` on this site, for emailed links.
+/// The target must be listed in the project's Auth redirect URLs.
+fn with_redirect_to(path: &str, target: &str) -> String {
+    match site_url(target) {
+        Some(url) => format!(
+            "{}?{}",
+            path,
+            serde_urlencoded::to_string([("redirect_to", url)]).unwrap_or_default()
+        ),
+        None => path.to_string(),
+    }
+}
+
+/// The session is refreshed this long before its access token expires.
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// First wait before trying again after a refresh failed offline; it doubles per failure.
+const REFRESH_RETRY_MS: u64 = 5_000;
+const MAX_REFRESH_RETRY_MS: u64 = 60_000;
+
+/// Milliseconds until a session expiring at `expires_at` should be refreshed.
+fn refresh_delay_ms(expires_at: i64, now_secs: i64) -> u64 {
+    (expires_at - REFRESH_MARGIN_SECS - now_secs).max(0) as u64 * 1000
+}
+
+/// Milliseconds until the next refresh attempt after `failures` refreshes in a row
+/// could not reach the server. Never sooner than the session itself asks for.
+fn refresh_retry_delay_ms(expires_at: i64, now_secs: i64, failures: u32) -> u64 {
+    let due = refresh_delay_ms(expires_at, now_secs);
+    if failures == 0 {
+        return due;
+    }
+    let backoff = REFRESH_RETRY_MS.saturating_mul(1 << (failures - 1).min(16)).min(MAX_REFRESH_RETRY_MS);
+    due.max(backoff)
+}
+
+/// Callers waiting on the refresh in flight, if there is one.
+type RefreshWaiters = Arc<Mutex<Option<Vec<oneshot::Sender<bool>>>>>;
+
+/// The one refresh request in flight. Waiters get its result when it finishes,
+/// or `false` if it is dropped first.
+struct RefreshFlight(RefreshWaiters);
+
+impl RefreshFlight {
+    fn finish(self, refreshed: bool) {
+        let waiters = self.0.lock().unwrap_or_else(|e| e.into_inner()).take();
+        for waiter in waiters.unwrap_or_default() {
+            let _ = waiter.send(refreshed);
+        }
+    }
+}
+
+impl Drop for RefreshFlight {
+    fn drop(&mut self) {
+        let waiters = self.0.lock().unwrap_or_else(|e| e.into_inner()).take();
+        for waiter in waiters.unwrap_or_default() {
+            let _ = waiter.send(false);
+        }
+    }
+}
+
+/// How a session written to storage by another tab changes this tab's.
+#[derive(Debug, PartialEq)]
+enum StoredSessionChange {
+    SignedOut,
+    Replaced(Box<Session>),
+    Unchanged,
+}
+
+/// Compare the new stored value with this tab's session. Values that do not
+/// parse are ignored rather than signing the user out.
+fn stored_session_change(current: Option<&Session>, new_value: Option<&str>) -> StoredSessionChange {
+    let Some(new_value) = new_value else {
+        return match current {
+            Some(_) => StoredSessionChange::SignedOut,
+            None => StoredSessionChange::Unchanged,
+        };
+    };
+    match serde_json::from_str::<Session>(new_value) {
+        Ok(stored) if current != Some(&stored) => StoredSessionChange::Replaced(Box::new(stored)),
+        _ => StoredSessionChange::Unchanged,
+    }
+}
 
 #[derive(Clone)]
 pub struct AuthContext {
@@ -31,17 +151,17 @@ pub struct AuthContext {
     pub is_loading: (ReadSignal<bool>, WriteSignal<bool>),
     pub error: (ReadSignal<Option<String>>, WriteSignal<Option<String>>),
     pub is_authenticated: (ReadSignal<bool>, WriteSignal<bool>),
+    /// Refreshes in a row that could not reach the server; the refresh timer retries while it is above zero.
+    refresh_failures: (ReadSignal<u32>, WriteSignal<u32>),
+    client: SupabaseClient,
+    refreshing: RefreshWaiters,
+    /// Signed in as the local admin of the in-memory profile, without GoTrue.
+    offline: bool,
 }
 
 impl AuthContext {
-    pub fn new() -> Self {
-        let context = Self {
-            user: signal::<Option<User>>(None),
-            session: signal::<Option<Session>>(None),
-            is_loading: signal(false),
-            error: signal(None),
-            is_authenticated: signal(false),
-        };
+    pub fn new(client: SupabaseClient) -> Self {
+        let context = Self::with_client(client, false);
         
         // Try to restore session from localStorage
         context.restore_session();
@@ -49,36 +169,84 @@ impl AuthContext {
         context
     }
 
+    /// Context for the in-memory profile, signed in as [`local_admin_session`]. The
+    /// session is never stored, so a Supabase profile does not pick it up later.
+    pub fn offline(client: SupabaseClient) -> Self {
+        let context = Self::with_client(client, true);
+        context.adopt_session(local_admin_session());
+        context
+    }
+
+    fn with_client(client: SupabaseClient, offline: bool) -> Self {
+        Self {
+            user: signal::<Option<User>>(None),
+            session: signal::<Option<Session>>(None),
+            is_loading: signal(false),
+            error: signal(None),
+            is_authenticated: signal(false),
+            refresh_failures: signal(0),
+            client,
+            refreshing: Arc::new(Mutex::new(None)),
+            offline,
+        }
+    }
+
+    /// Load the stored session so the app starts signed in. It is only trusted
+    /// until `verify_session` has checked it with the server.
     fn restore_session(&self) {
-        // First try to restore a Supabase session
+        LocalStorage::delete(LEGACY_LOCAL_SESSION_KEY);
+
         if let Ok(stored_session) = LocalStorage::get::<Session>(AUTH_STORAGE_KEY) {
-            // Check if session is still valid (not expired)
-            let now = js_sys::Date::now() as i64 / 1000; // Current time in seconds
-            if stored_session.expires_at > now {
-                self.session.1.set(Some(stored_session.clone()));
-                self.user.1.set(Some(stored_session.user));
-                self.is_authenticated.1.set(true);
+            // An expired access token is kept: the refresh timer replaces it right away
+            if !stored_session.refresh_token.is_empty() {
+                self.adopt_session(stored_session);
                 logging::log!("Supabase session restored from localStorage");
-                return;
             } else {
-                // Session expired, remove it
                 LocalStorage::delete(AUTH_STORAGE_KEY);
-                logging::log!("Stored Supabase session expired, cleared");
+                logging::log!("Stored Supabase session has no refresh token, cleared");
             }
         }
-        
-        // If no Supabase session, try to restore a local session
-        if let Some(local_session) = self.load_local_session() {
-            let now = js_sys::Date::now() / 1000.0; // Current time in seconds
-            if local_session.expires_at > now as i64 {
-                self.user.1.set(Some(local_session.user));
-                self.is_authenticated.1.set(true);
-                logging::log!("Local session restored from localStorage");
-            } else {
-                // Local session expired, remove it
-                LocalStorage::delete(LOCAL_SESSION_KEY);
-                logging::log!("Stored local session expired, cleared");
+    }
+
+    /// Check the restored session against `/auth/v1/user`, which also brings in
+    /// changes to the user made elsewhere, such as a new role. A rejected token is
+    /// refreshed, and the user is signed out if that fails too. Offline, the
+    /// session is kept as is.
+    pub async fn verify_session(&self) {
+        let Some(session) = self.session.0.get_untracked() else {
+            return;
+        };
+        self.is_loading.1.set(true);
+
+        match self.fetch_user(&session.access_token).await {
+            Ok(user) => {
+                self.start_session(Session { user, ..session });
+                logging::log!("Stored session verified");
+            }
+            Err(err) if matches!(err.status(), Some(401 | 403)) => {
+                logging::log!("Stored session rejected, refreshing: {}", err);
+                self.refresh_session().await;
+            }
+            Err(err) => logging::log!("Stored session not verified: {}", err),
+        }
+
+        self.is_loading.1.set(false);
+    }
+
+    /// Follow a change another tab made to the stored session: signing in or out
+    /// there, or a refresh there, applies here too.
+    fn sync_stored_session(&self, new_value: Option<String>) {
+        let current = self.session.0.get_untracked();
+        match stored_session_change(current.as_ref(), new_value.as_deref()) {
+            StoredSessionChange::SignedOut => {
+                self.forget_session();
+                logging::log!("Signed out in another tab");
             }
+            StoredSessionChange::Replaced(session) => {
+                self.adopt_session(*session);
+                logging::log!("Session updated in another tab");
+            }
+            StoredSessionChange::Unchanged => {}
         }
     }
 
@@ -88,76 +256,58 @@ impl AuthContext {
         }
     }
 
-    fn store_local_session(&self, local_session: &LocalSession) {
-        if let Err(e) = LocalStorage::set(LOCAL_SESSION_KEY, local_session) {
-            logging::log!("Failed to store local session: {:?}", e);
-        }
+    /// Persist a session and mark its user as signed in.
+    fn start_session(&self, session: Session) {
+        self.store_session(&session);
+        self.adopt_session(session);
     }
 
-    fn load_local_session(&self) -> Option<LocalSession> {
-        match LocalStorage::get(LOCAL_SESSION_KEY) {
-            Ok(local_session) => Some(local_session),
-            Err(_) => None,
-        }
+    /// Mark the user of an already stored session as signed in.
+    fn adopt_session(&self, session: Session) {
+        // Data requests carry the user's JWT so row-level security sees the editor
+        self.client.set_access_token(Some(session.access_token.clone()));
+        self.session.1.set(Some(session.clone()));
+        self.user.1.set(Some(session.user));
+        self.is_authenticated.1.set(true);
+        self.refresh_failures.1.set(0);
     }
 
     fn clear_session(&self) {
         LocalStorage::delete(AUTH_STORAGE_KEY);
-        LocalStorage::delete(LOCAL_SESSION_KEY);
+        self.forget_session();
+    }
+
+    /// Mark the user as signed out without touching storage.
+    fn forget_session(&self) {
+        self.client.set_access_token(None);
         self.session.1.set(None);
         self.user.1.set(None);
         self.is_authenticated.1.set(false);
+        self.refresh_failures.1.set(0);
     }
 
     pub async fn sign_in(&self, email: String, password: String) {
         self.is_loading.1.set(true);
         self.error.1.set(None);
 
+        // Any credentials sign the local admin back in
+        if self.offline {
+            self.adopt_session(local_admin_session());
+            self.is_loading.1.set(false);
+            return;
+        }
+
         let request = SignInRequest { email, password };
         
-        match self.auth_request::<AuthResponse, SignInRequest>("/auth/v1/token?grant_type=password", &request).await {
-            Ok(auth_response) => {
-                if let Some(session) = auth_response.session {
-                    self.store_session(&session);
-                    self.session.1.set(Some(session.clone()));
-                    self.user.1.set(Some(session.user));
-                    self.is_authenticated.1.set(true);
-                    logging::log!("Sign in successful");
-                } else if let Some(user) = auth_response.user {
-                    // User exists and email is confirmed, but no session was created
-                    // Create our own local session instead of relying on Supabase
-                    logging::log!("User confirmed but no Supabase session. Creating local session...");
-                    
-                    if user.email_confirmed_at.is_some() {
-                        // Create a local session that expires in 24 hours (using js timestamp)
-                        let now = js_sys::Date::now() / 1000.0; // Convert to seconds
-                        let local_session = LocalSession {
-                            user: user.clone(),
-                            created_at: now as i64,
-                            expires_at: (now + (24.0 * 60.0 * 60.0)) as i64, // 24 hours
-                        };
-                        
-                        // Store the local session
-                        self.store_local_session(&local_session);
-                        
-                        // Set the auth state
-                        self.user.1.set(Some(user));
-                        self.is_authenticated.1.set(true);
-                        
-                        logging::log!("Local session created successfully. User is now authenticated.");
-                        self.error.1.set(None); // Clear any previous errors
-                    } else {
-                        logging::log!("Sign in failed: Email not confirmed");
-                        self.error.1.set(Some("Please check your email and confirm your account before signing in.".to_string()));
-                    }
-                } else {
-                    logging::log!("Sign in failed: No session or user returned");
-                    self.error.1.set(Some("Invalid email or password".to_string()));
-                }
+        // Unconfirmed emails and wrong passwords come back as errors
+        match self.auth_request::<Session, SignInRequest>("/auth/v1/token?grant_type=password", &request).await {
+            Ok(session) => {
+                self.start_session(session);
+                logging::log!("Sign in successful");
             }
             Err(err) => {
                 logging::log!("Sign in error: {}", err);
-                self.error.1.set(Some(err));
+                self.error.1.set(Some(err.to_string()));
             }
         }
         
@@ -177,10 +327,7 @@ impl AuthContext {
         match self.auth_request::<AuthResponse, SignUpRequest>("/auth/v1/signup", &request).await {
             Ok(auth_response) => {
                 if let Some(session) = auth_response.session {
-                    self.store_session(&session);
-                    self.session.1.set(Some(session.clone()));
-                    self.user.1.set(Some(session.user));
-                    self.is_authenticated.1.set(true);
+                    self.start_session(session);
                     logging::log!("Sign up successful");
                 } else if let Some(_user) = auth_response.user {
                     // User created but needs email confirmation
@@ -192,7 +339,7 @@ impl AuthContext {
             }
             Err(err) => {
                 logging::log!("Sign up error: {}", err);
-                self.error.1.set(Some(err));
+                self.error.1.set(Some(err.to_string()));
             }
         }
         
@@ -203,9 +350,16 @@ impl AuthContext {
         self.is_loading.1.set(true);
         self.error.1.set(None);
 
-        // Call the logout endpoint
-        if let Some(session) = self.session.0.get() {
-            let _: Result<serde_json::Value, String> = self.auth_request_with_token("/auth/v1/logout", &serde_json::json!({}), &session.access_token).await;
+        if self.offline {
+            self.forget_session();
+            self.is_loading.1.set(false);
+            return;
+        }
+
+        // Revoke the refresh token; logout answers 204, so the body is not parsed
+        if let Some(session) = self.session.0.get_untracked() {
+            let authorization = format!("Bearer {}", session.access_token);
+            let _ = self.client.send(Method::POST, &self.client.auth_endpoint("/auth/v1/logout"), &[("Authorization", &authorization)], None).await;
         }
 
         self.clear_session();
@@ -218,126 +372,417 @@ impl AuthContext {
         self.error.1.set(None);
 
         let request = ResetPasswordRequest { email };
+        // The emailed link comes back to the new-password form
+        let path = with_redirect_to("/auth/v1/recover", UPDATE_PASSWORD_PATH);
         
-        match self.auth_request::<serde_json::Value, ResetPasswordRequest>("/auth/v1/recover", &request).await {
+        match self.auth_request::<serde_json::Value, ResetPasswordRequest>(&path, &request).await {
             Ok(_) => {
                 logging::log!("Password reset email sent");
                 self.error.1.set(Some("Password reset email sent. Please check your inbox.".to_string()));
             }
             Err(err) => {
                 logging::log!("Password reset error: {}", err);
-                self.error.1.set(Some(err));
+                self.error.1.set(Some(err.to_string()));
             }
         }
         
         self.is_loading.1.set(false);
     }
 
-    pub async fn update_user(&self, update_request: UpdateUserRequest) {
+    /// Email a magic link and a one-time code to an existing account.
+    /// Returns whether the email was sent.
+    pub async fn send_otp(&self, email: String) -> bool {
+        self.is_loading.1.set(true);
+        self.error.1.set(None);
+
+        let request = OtpRequest { email, create_user: false };
+        let path = with_redirect_to("/auth/v1/otp", AUTH_CALLBACK_PATH);
+        let sent = match self.auth_request::<serde_json::Value, OtpRequest>(&path, &request).await {
+            Ok(_) => {
+                logging::log!("Sign-in email sent");
+                true
+            }
+            Err(err) => {
+                logging::log!("Sign-in email error: {}", err);
+                self.error.1.set(Some(err.to_string()));
+                false
+            }
+        };
+
+        self.is_loading.1.set(false);
+        sent
+    }
+
+    /// Sign in with the one-time code from the sign-in email.
+    pub async fn verify_otp(&self, request: VerifyOtpRequest) -> bool {
+        self.is_loading.1.set(true);
+        self.error.1.set(None);
+
+        let verified = match self.auth_request::<Session, VerifyOtpRequest>("/auth/v1/verify", &request).await {
+            Ok(session) => {
+                self.start_session(session);
+                logging::log!("Signed in with a one-time code");
+                true
+            }
+            Err(err) => {
+                logging::log!("One-time code error: {}", err);
+                self.error.1.set(Some(err.to_string()));
+                false
+            }
+        };
+
+        self.is_loading.1.set(false);
+        verified
+    }
+
+    /// Leave for the OAuth provider's consent page. The PKCE verifier stays in
+    /// storage until the provider sends the browser back to the callback route.
+    pub fn sign_in_with_provider(&self, provider: &str) {
+        self.error.1.set(None);
+
+        let mut random = [0u8; 32];
+        let filled = window().crypto()
+            .and_then(|crypto| crypto.get_random_values_with_u8_array(&mut random))
+            .is_ok();
+        let redirect_to = site_url(AUTH_CALLBACK_PATH);
+        let (true, Some(redirect_to)) = (filled, redirect_to) else {
+            self.error.1.set(Some("This browser cannot start a secure sign-in.".to_string()));
+            return;
+        };
+
+        let verifier = pkce::code_verifier(&random);
+        if let Err(e) = LocalStorage::set(VERIFIER_STORAGE_KEY, &verifier) {
+            logging::log!("Failed to store PKCE verifier: {:?}", e);
+            self.error.1.set(Some("Could not start the sign-in. Is storage disabled?".to_string()));
+            return;
+        }
+
+        let url = pkce::authorize_url(&self.client.auth_endpoint("/auth/v1/authorize"), provider, &redirect_to, &verifier);
+        if window().location().set_href(&url).is_err() {
+            self.error.1.set(Some("Could not open the sign-in page.".to_string()));
+        }
+    }
+
+    /// Finish an OAuth sign-in: trade the callback's code and the stored verifier for a session.
+    pub async fn exchange_code(&self, code: String) -> bool {
+        self.is_loading.1.set(true);
+        self.error.1.set(None);
+
+        let Ok(code_verifier) = LocalStorage::get::<String>(VERIFIER_STORAGE_KEY) else {
+            self.error.1.set(Some("This sign-in was started in another browser or has already been used. Please try again.".to_string()));
+            self.is_loading.1.set(false);
+            return false;
+        };
+        // A verifier is good for one exchange, whatever the outcome
+        LocalStorage::delete(VERIFIER_STORAGE_KEY);
+
+        let request = PkceTokenRequest { auth_code: code, code_verifier };
+        let signed_in = match self.auth_request::<Session, PkceTokenRequest>("/auth/v1/token?grant_type=pkce", &request).await {
+            Ok(session) => {
+                self.start_session(session);
+                logging::log!("Signed in with OAuth");
+                true
+            }
+            Err(err) => {
+                logging::log!("OAuth code exchange error: {}", err);
+                self.error.1.set(Some(err.to_string()));
+                false
+            }
+        };
+
+        self.is_loading.1.set(false);
+        signed_in
+    }
+
+    /// Sign in with the tokens a magic link handed back in the URL fragment.
+    pub async fn sign_in_with_link(&self, tokens: FragmentTokens) -> bool {
+        self.is_loading.1.set(true);
+        self.error.1.set(None);
+
+        let signed_in = match self.session_from_link(tokens).await {
+            Ok(session) => {
+                self.start_session(session);
+                logging::log!("Signed in with a magic link");
+                true
+            }
+            Err(err) => {
+                logging::log!("Magic link error: {}", err);
+                self.error.1.set(Some(err.to_string()));
+                false
+            }
+        };
+
+        self.is_loading.1.set(false);
+        signed_in
+    }
+
+    /// Turn the tokens from an emailed link into a session without signing in yet:
+    /// the user behind the token is fetched, and nothing is stored.
+    pub async fn session_from_link(&self, tokens: FragmentTokens) -> Result<Session, SupabaseError> {
+        let user = self.fetch_user(&tokens.access_token).await?;
+        Ok(tokens.into_session(user))
+    }
+
+    /// Set a new password with the temporary session from a recovery link,
+    /// then sign in with that session. Returns whether the password was changed.
+    pub async fn update_password(&self, session: Session, request: UpdatePasswordRequest) -> bool {
+        self.is_loading.1.set(true);
+        self.error.1.set(None);
+
+        let result = self.auth_request_with_token::<User, UpdatePasswordRequest>("/auth/v1/user", &request, &session.access_token).await;
+        let updated = match result {
+            Ok(user) => {
+                self.start_session(Session { user, ..session });
+                logging::log!("Password updated");
+                true
+            }
+            Err(err) => {
+                logging::log!("Password update error: {}", err);
+                self.error.1.set(Some(err.to_string()));
+                false
+            }
+        };
+
+        self.is_loading.1.set(false);
+        updated
+    }
+
+    async fn fetch_user(&self, access_token: &str) -> Result<User, SupabaseError> {
+        let path = "/auth/v1/user";
+        let authorization = format!("Bearer {}", access_token);
+        let response = self.client.send(Method::GET, &self.client.auth_endpoint(path), &[("Authorization", &authorization)], None).await?;
+
+        if response.ok() {
+            response.json()
+        } else {
+            Err(response.error(path))
+        }
+    }
+
+    /// Change the signed-in user's email, password or metadata. Only the fields
+    /// set in the request are sent. Returns whether the user was updated.
+    pub async fn update_user(&self, update_request: UpdateUserRequest) -> bool {
         self.is_loading.1.set(true);
         self.error.1.set(None);
 
-        if let Some(session) = self.session.0.get() {
-            match self.auth_request_with_token::<AuthResponse, UpdateUserRequest>("/auth/v1/user", &update_request, &session.access_token).await {
-                Ok(auth_response) => {
-                    if let Some(user) = auth_response.user {
-                        self.user.1.set(Some(user));
+        let updated = match self.session.0.get_untracked() {
+            Some(session) => {
+                match self.auth_request_with_token::<User, UpdateUserRequest>("/auth/v1/user", &update_request, &session.access_token).await {
+                    Ok(user) => {
+                        self.start_session(Session { user, ..session });
                         logging::log!("User updated successfully");
+                        true
+                    }
+                    Err(err) => {
+                        logging::log!("Update user error: {}", err);
+                        self.error.1.set(Some(err.to_string()));
+                        false
                     }
-                }
-                Err(err) => {
-                    logging::log!("Update user error: {}", err);
-                    self.error.1.set(Some(err));
                 }
             }
-        } else {
+            None => {
+                self.error.1.set(Some("No active session".to_string()));
+                false
+            }
+        };
+
+        self.is_loading.1.set(false);
+        updated
+    }
+
+    /// Set a new password for the signed-in user.
+    pub async fn change_password(&self, request: UpdatePasswordRequest) -> bool {
+        self.update_user(UpdateUserRequest {
+            password: Some(request.password),
+            ..Default::default()
+        }).await
+    }
+
+    /// Upload `file` to the avatars bucket under the user's folder and point
+    /// `user_metadata.avatar_url` at it.
+    pub async fn upload_avatar(&self, file: File) -> bool {
+        let Some(user) = self.user.0.get_untracked() else {
             self.error.1.set(Some("No active session".to_string()));
+            return false;
+        };
+        if let Err(err) = check_avatar(&file.type_(), file.size()) {
+            self.error.1.set(Some(err));
+            return false;
         }
-        
+
+        self.is_loading.1.set(true);
+        self.error.1.set(None);
+        let avatars = self.client.storage(AVATARS_BUCKET);
+        let path = avatar_path(&user.id, &file.name(), js_sys::Date::now() as u64);
+        let uploaded = avatars.upload(&path, &file, &file.type_()).await;
         self.is_loading.1.set(false);
+
+        match uploaded {
+            Ok(path) => {
+                let metadata = UserMetadata { avatar_url: Some(avatars.public_url(&path)), ..Default::default() };
+                self.update_user(UpdateUserRequest { data: Some(metadata), ..Default::default() }).await
+            }
+            Err(err) => {
+                logging::log!("Avatar upload error: {}", err);
+                self.error.1.set(Some(err.to_string()));
+                false
+            }
+        }
     }
 
-    pub async fn refresh_session(&self) {
-        if let Some(session) = self.session.0.get() {
-            self.is_loading.1.set(true);
-            
-            let refresh_request = serde_json::json!({
-                "refresh_token": session.refresh_token
-            });
-            
-            match self.auth_request::<AuthResponse, serde_json::Value>("/auth/v1/token?grant_type=refresh_token", &refresh_request).await {
-                Ok(auth_response) => {
-                    if let Some(new_session) = auth_response.session {
-                        self.store_session(&new_session);
-                        self.session.1.set(Some(new_session.clone()));
-                        self.user.1.set(Some(new_session.user));
-                        logging::log!("Session refreshed successfully");
-                    }
+    /// Add an unverified TOTP factor for the signed-in user. It counts once
+    /// `verify_totp` has checked a code from it.
+    pub async fn enroll_totp(&self) -> Option<EnrolledFactor> {
+        let session = self.session.0.get_untracked()?;
+        self.error.1.set(None);
+
+        let authorization = format!("Bearer {}", session.access_token);
+        match self.send_auth(Method::POST, "/auth/v1/factors", &EnrollFactorRequest::totp(), &[("Authorization", &authorization)]).await {
+            Ok(factor) => Some(factor),
+            Err(err) => {
+                logging::log!("MFA enrollment error: {}", err);
+                self.error.1.set(Some(err.to_string()));
+                None
+            }
+        }
+    }
+
+    /// Challenge a factor and answer with `code`. On success the session is
+    /// replaced by one at `aal2`, and an enrolled factor becomes verified.
+    pub async fn verify_totp(&self, factor_id: &str, code: String) -> bool {
+        let Some(session) = self.session.0.get_untracked() else {
+            self.error.1.set(Some("No active session".to_string()));
+            return false;
+        };
+        self.is_loading.1.set(true);
+        self.error.1.set(None);
+
+        let authorization = format!("Bearer {}", session.access_token);
+        let headers = [("Authorization", authorization.as_str())];
+        let challenge_path = format!("/auth/v1/factors/{}/challenge", factor_id);
+        let result = match self.send_auth::<FactorChallenge, _>(Method::POST, &challenge_path, &serde_json::json!({}), &headers).await {
+            Ok(challenge) => {
+                let request = VerifyFactorRequest { challenge_id: challenge.id, code };
+                let verify_path = format!("/auth/v1/factors/{}/verify", factor_id);
+                self.send_auth::<Session, _>(Method::POST, &verify_path, &request, &headers).await
+            }
+            Err(err) => Err(err),
+        };
+
+        let verified = match result {
+            Ok(new_session) => {
+                self.start_session(new_session);
+                logging::log!("Second factor verified");
+                true
+            }
+            Err(err) => {
+                logging::log!("MFA verification error: {}", err);
+                self.error.1.set(Some(err.to_string()));
+                false
+            }
+        };
+
+        self.is_loading.1.set(false);
+        verified
+    }
+
+    /// Exchange the refresh token for a new session. Concurrent callers share one
+    /// request, since a refresh token can only be used once. Returns whether the
+    /// session was refreshed; when the server rejects the refresh token the user is
+    /// signed out.
+    pub async fn refresh_session(&self) -> bool {
+        let waiting = {
+            let mut refreshing = self.refreshing.lock().unwrap_or_else(|e| e.into_inner());
+            match refreshing.as_mut() {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
                 }
-                Err(err) => {
-                    logging::log!("Session refresh error: {}", err);
-                    self.clear_session();
+                None => {
+                    *refreshing = Some(vec![]);
+                    None
                 }
             }
-            
-            self.is_loading.1.set(false);
+        };
+        if let Some(receiver) = waiting {
+            return receiver.await.unwrap_or(false);
         }
+
+        let flight = RefreshFlight(self.refreshing.clone());
+        let refreshed = self.request_refresh().await;
+        flight.finish(refreshed);
+        refreshed
     }
 
-    async fn auth_request<T, U>(&self, path: &str, payload: &U) -> Result<T, String>
+    async fn request_refresh(&self) -> bool {
+        let Some(session) = self.session.0.get_untracked() else {
+            return false;
+        };
+        let request = RefreshTokenRequest { refresh_token: session.refresh_token };
+
+        match self.auth_request::<Session, RefreshTokenRequest>("/auth/v1/token?grant_type=refresh_token", &request).await {
+            Ok(new_session) => {
+                self.start_session(new_session);
+                logging::log!("Session refreshed successfully");
+                true
+            }
+            // Keep the session while offline; the refresh timer tries again with backoff
+            Err(err) if err.is_offline() => {
+                logging::log!("Session refresh postponed: {}", err);
+                self.refresh_failures.1.update(|n| *n += 1);
+                false
+            }
+            Err(err) => {
+                // Another tab may have used the refresh token first; its session is in storage
+                if let Ok(stored) = LocalStorage::get::<Session>(AUTH_STORAGE_KEY) {
+                    if stored.refresh_token != request.refresh_token {
+                        self.adopt_session(stored);
+                        logging::log!("Session was refreshed in another tab");
+                        return true;
+                    }
+                }
+                logging::log!("Session refresh error: {}", err);
+                self.clear_session();
+                self.error.1.set(Some(SupabaseError::AuthExpired.to_string()));
+                false
+            }
+        }
+    }
+
+    async fn auth_request<T, U>(&self, path: &str, payload: &U) -> Result<T, SupabaseError>
     where
         T: serde::de::DeserializeOwned,
         U: serde::Serialize,
     {
-        let config = SupabaseConfig::new();
-        let url = format!("{}{}", config.url, path);
-        
-        let request = Request::post(&url)
-            .header("apikey", &config.api_key)
-            .header("Content-Type", "application/json")
-            .json(payload)
-            .map_err(|e| e.to_string())?;
+        self.send_auth(Method::POST, path, payload, &[]).await
+    }
 
-        let response = request.send().await.map_err(|e| e.to_string())?;
-        
-        if response.ok() {
-            response.json::<T>().await.map_err(|e| e.to_string())
-        } else {
-            let error_text = response.text().await.unwrap_or_default();
-            if let Ok(auth_error) = serde_json::from_str::<AuthError>(&error_text) {
-                Err(auth_error.message)
-            } else {
-                Err(format!("HTTP {}: {}", response.status(), error_text))
-            }
-        }
+    async fn auth_request_with_token<T, U>(&self, path: &str, payload: &U, token: &str) -> Result<T, SupabaseError>
+    where
+        T: serde::de::DeserializeOwned,
+        U: serde::Serialize,
+    {
+        let authorization = format!("Bearer {}", token);
+        self.send_auth(Method::PUT, path, payload, &[("Authorization", &authorization)]).await
     }
 
-    async fn auth_request_with_token<T, U>(&self, path: &str, payload: &U, token: &str) -> Result<T, String>
+    async fn send_auth<T, U>(&self, method: Method, path: &str, payload: &U, headers: &[(&str, &str)]) -> Result<T, SupabaseError>
     where
         T: serde::de::DeserializeOwned,
         U: serde::Serialize,
     {
-        let config = SupabaseConfig::new();
-        let url = format!("{}{}", config.url, path);
-        
-        let request = Request::put(&url)
-            .header("apikey", &config.api_key)
-            .header("Authorization", &format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(payload)
-            .map_err(|e| e.to_string())?;
-
-        let response = request.send().await.map_err(|e| e.to_string())?;
-        
+        let body = serde_json::to_string(payload)
+            .map_err(|e| SupabaseError::Request(e.to_string()))?;
+        let mut headers = headers.to_vec();
+        headers.push(("Content-Type", "application/json"));
+
+        let response = self.client.send(method, &self.client.auth_endpoint(path), &headers, Some(&body)).await?;
+
         if response.ok() {
-            response.json::<T>().await.map_err(|e| e.to_string())
+            response.json()
         } else {
-            let error_text = response.text().await.unwrap_or_default();
-            if let Ok(auth_error) = serde_json::from_str::<AuthError>(&error_text) {
-                Err(auth_error.message)
-            } else {
-                Err(format!("HTTP {}: {}", response.status(), error_text))
-            }
+            Err(response.error(path))
         }
     }
 
@@ -352,11 +797,102 @@ impl AuthContext {
     pub fn is_authenticated(&self) -> bool {
         self.is_authenticated.0.get()
     }
+
+    /// Role of the signed-in user, `None` when signed out.
+    pub fn role(&self) -> Option<Role> {
+        self.user.0.get().map(|user| Role::of(&user))
+    }
+
+    /// Assurance level of the current session, `None` when signed out.
+    pub fn aal(&self) -> Option<Aal> {
+        self.session.0.with(|session| session.as_ref().and_then(|session| token_aal(&session.access_token)))
+    }
+
+    /// Whether the user has a verified second factor but this session has not used it yet.
+    pub fn needs_mfa(&self) -> bool {
+        let has_factor = self.user.0.with(|user| user.as_ref().is_some_and(|user| user.totp_factor().is_some()));
+        has_factor && self.aal() != Some(Aal::Aal2)
+    }
+
+    /// Whether the signed-in user may do something. Tracks the user, so views update
+    /// when the session changes.
+    pub fn can(&self, permission: Permission) -> bool {
+        self.role().is_some_and(|role| role.can(permission))
+    }
 }
 
 #[component]
 pub fn AuthProvider(children: Children) -> impl IntoView {
-    let auth_context = AuthContext::new();
+    let client = use_supabase();
+
+    // The in-memory profile has no GoTrue to talk to, so nothing is refreshed or verified
+    if use_environment().backend == BackendKind::Memory {
+        provide_context(AuthContext::offline(client));
+        return children();
+    }
+
+    let auth_context = AuthContext::new(client.clone());
+
+    // Data requests rejected with 401 refresh the session and try once more
+    client.on_unauthorized(Arc::new({
+        let auth = auth_context.clone();
+        move || {
+            let auth = auth.clone();
+            Box::pin(async move { auth.refresh_session().await })
+        }
+    }));
+
+    // Refresh shortly before the access token expires; rescheduled for each new session
+    // and after each refresh that could not reach the server
+    let session = auth_context.session.0;
+    let refresh_failures = auth_context.refresh_failures.0;
+    let auth = auth_context.clone();
+    Effect::new(move |previous: Option<Option<TimeoutHandle>>| {
+        if let Some(Some(timer)) = previous {
+            timer.clear();
+        }
+        let failures = refresh_failures.get();
+        let session = session.get()?;
+        let now = js_sys::Date::now() as i64 / 1000;
+        let auth = auth.clone();
+        set_timeout_with_handle(
+            move || spawn_local(async move {
+                auth.refresh_session().await;
+            }),
+            Duration::from_millis(refresh_retry_delay_ms(session.expires_at, now, failures)),
+        ).ok()
+    });
+
+    // Back online, a postponed refresh need not wait for its backoff
+    let _ = use_event_listener(window(), ev::online, {
+        let auth = auth_context.clone();
+        move |_| {
+            if auth.refresh_failures.0.get_untracked() > 0 {
+                let auth = auth.clone();
+                spawn_local(async move {
+                    auth.refresh_session().await;
+                });
+            }
+        }
+    });
+
+    // Check the restored session with the server before relying on it
+    spawn_local({
+        let auth = auth_context.clone();
+        async move { auth.verify_session().await }
+    });
+
+    // Other tabs share localStorage; follow their sign-ins, sign-outs and refreshes
+    let _ = use_event_listener(window(), ev::storage, {
+        let auth = auth_context.clone();
+        move |event: StorageEvent| {
+            // `key` is null when the whole storage was cleared
+            if event.key().is_none_or(|key| key == AUTH_STORAGE_KEY) {
+                auth.sync_stored_session(event.new_value());
+            }
+        }
+    });
+
     provide_context(auth_context);
     children()
 }
@@ -365,3 +901,61 @@ pub fn use_auth() -> AuthContext {
     use_context::<AuthContext>()
         .expect("AuthContext must be provided")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_is_scheduled_ahead_of_expiry() {
+        assert_eq!(refresh_delay_ms(1_000, 400), 540_000);
+        assert_eq!(refresh_delay_ms(1_000, 950), 0);
+        assert_eq!(refresh_delay_ms(1_000, 2_000), 0);
+    }
+
+    #[test]
+    fn refresh_is_retried_with_backoff_after_failing_offline() {
+        // Past the margin, a failed refresh must not retry in a tight loop
+        assert_eq!(refresh_retry_delay_ms(1_000, 2_000, 0), 0);
+        assert_eq!(refresh_retry_delay_ms(1_000, 2_000, 1), 5_000);
+        assert_eq!(refresh_retry_delay_ms(1_000, 2_000, 3), 20_000);
+        assert_eq!(refresh_retry_delay_ms(1_000, 2_000, 10), 60_000);
+        assert_eq!(refresh_retry_delay_ms(1_000, 2_000, u32::MAX), 60_000);
+        // A session that is not due yet keeps its own schedule
+        assert_eq!(refresh_retry_delay_ms(1_000, 400, 2), 540_000);
+    }
+
+    fn session(access_token: &str) -> Session {
+        serde_json::from_value(serde_json::json!({
+            "access_token": access_token,
+            "token_type": "bearer",
+            "expires_in": 3600,
+            "expires_at": 1_700_003_600,
+            "refresh_token": "refresh",
+            "user": {
+                "id": "8c1e",
+                "aud": "authenticated",
+                "email": "ada@example.com",
+                "created_at": "2026-10-18T12:00:00Z",
+                "updated_at": "2026-10-18T12:00:00Z",
+            },
+        })).unwrap()
+    }
+
+    #[test]
+    fn other_tabs_sign_out_and_refresh() {
+        let current = session("old");
+        let refreshed = serde_json::to_string(&session("new")).unwrap();
+        let same = serde_json::to_string(&current).unwrap();
+
+        assert_eq!(stored_session_change(Some(&current), None), StoredSessionChange::SignedOut);
+        assert_eq!(stored_session_change(None, None), StoredSessionChange::Unchanged);
+        assert_eq!(
+            stored_session_change(Some(&current), Some(&refreshed)),
+            StoredSessionChange::Replaced(Box::new(session("new")))
+        );
+        assert_eq!(stored_session_change(None, Some(&refreshed)), StoredSessionChange::Replaced(Box::new(session("new"))));
+        assert_eq!(stored_session_change(Some(&current), Some(&same)), StoredSessionChange::Unchanged);
+        assert_eq!(stored_session_change(Some(&current), Some("not json")), StoredSessionChange::Unchanged);
+    }
+}
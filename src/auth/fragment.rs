@@ -0,0 +1,102 @@
+use serde::Deserialize;
+
+use crate::auth::model::{Session, User};
+
+/// Parameters Supabase Auth appends to the URL fragment when an emailed link
+/// (password recovery, magic link) sends the user back to the site.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct FragmentParams {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+    expires_at: Option<i64>,
+    token_type: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Session tokens handed over in the fragment. The user is not part of it
+/// and is fetched with the access token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FragmentTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+    pub expires_at: i64,
+    pub token_type: String,
+    /// What the link was sent for: `recovery`, `magiclink`, `signup`...
+    pub kind: Option<String>,
+}
+
+/// Access tokens last an hour unless the project says otherwise.
+const DEFAULT_EXPIRES_IN: i64 = 3600;
+
+impl FragmentTokens {
+    /// Read the tokens from a fragment such as `#access_token=...&type=recovery`.
+    /// An expired or already used link comes back with an error description instead.
+    pub fn parse(fragment: &str, now_secs: i64) -> Result<Self, String> {
+        let params: FragmentParams = serde_urlencoded::from_str(fragment.trim_start_matches('#'))
+            .map_err(|_| "This link is not valid.".to_string())?;
+
+        if params.error.is_some() || params.error_description.is_some() {
+            return Err(params.error_description
+                .or(params.error)
+                .unwrap_or_default());
+        }
+
+        match (params.access_token, params.refresh_token) {
+            (Some(access_token), Some(refresh_token)) => {
+                let expires_in = params.expires_in.unwrap_or(DEFAULT_EXPIRES_IN);
+                Ok(Self {
+                    access_token,
+                    refresh_token,
+                    expires_in,
+                    expires_at: params.expires_at.unwrap_or(now_secs + expires_in),
+                    token_type: params.token_type.unwrap_or_else(|| "bearer".to_string()),
+                    kind: params.kind,
+                })
+            }
+            _ => Err("This link is missing its sign-in token. Please request a new one.".to_string()),
+        }
+    }
+
+    pub fn into_session(self, user: User) -> Session {
+        Session {
+            access_token: self.access_token,
+            token_type: self.token_type,
+            expires_in: self.expires_in,
+            expires_at: self.expires_at,
+            refresh_token: self.refresh_token,
+            user,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_recovery_tokens() {
+        let tokens = FragmentTokens::parse(
+            "#access_token=abc&expires_in=3600&refresh_token=def&token_type=bearer&type=recovery",
+            1_000,
+        ).unwrap();
+        assert_eq!(tokens.access_token, "abc");
+        assert_eq!(tokens.refresh_token, "def");
+        assert_eq!(tokens.expires_at, 4_600);
+        assert_eq!(tokens.kind.as_deref(), Some("recovery"));
+    }
+
+    #[test]
+    fn reports_link_errors() {
+        let error = FragmentTokens::parse(
+            "#error=access_denied&error_code=otp_expired&error_description=Email+link+is+invalid+or+has+expired",
+            0,
+        ).unwrap_err();
+        assert_eq!(error, "Email link is invalid or has expired");
+        assert!(FragmentTokens::parse("", 0).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use crate::auth::model::{AppMetadata, Session, User};
+use crate::auth::permissions::Role;
+
+/// Session of a fixed admin for the in-memory profile, which has no GoTrue to sign in
+/// with. Its token claims `aal2`, so the editor opens without asking for a second factor.
+/// Nothing checks the token: the in-memory backend ignores it.
+pub fn local_admin_session() -> Session {
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"none","typ":"JWT"}"#);
+    let claims = URL_SAFE_NO_PAD.encode(r#"{"sub":"local-admin","role":"authenticated","aal":"aal2"}"#);
+    Session {
+        access_token: format!("{}.{}.local", header, claims),
+        token_type: "bearer".to_string(),
+        expires_in: 0,
+        expires_at: i64::MAX,
+        refresh_token: String::new(),
+        user: User {
+            id: "local-admin".to_string(),
+            email: "admin@localhost".to_string(),
+            email_confirmed_at: None,
+            phone: None,
+            created_at: None,
+            updated_at: None,
+            last_sign_in_at: None,
+            banned_until: None,
+            app_metadata: Some(AppMetadata {
+                provider: None,
+                providers: None,
+                role: Some(Role::Admin.as_str().to_string()),
+            }),
+            user_metadata: None,
+            factors: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::mfa::{token_aal, Aal};
+    use crate::auth::permissions::Permission;
+
+    #[test]
+    fn local_admin_may_do_everything_at_aal2() {
+        let session = local_admin_session();
+        let role = Role::of(&session.user);
+        assert_eq!(role, Role::Admin);
+        assert!(role.can(Permission::Delete) && role.can(Permission::ManageUsers));
+        assert_eq!(token_aal(&session.access_token), Some(Aal::Aal2));
+    }
+}
@@ -0,0 +1,43 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::Deserialize;
+
+/// Authenticator assurance level of a session: `Aal2` once a second factor was verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aal {
+    Aal1,
+    Aal2,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    aal: Option<String>,
+}
+
+/// The `aal` claim of an access token. The signature is not checked: the server
+/// does that, this only decides which step of sign-in to show.
+pub fn token_aal(access_token: &str) -> Option<Aal> {
+    let payload = access_token.split('.').nth(1)?;
+    let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+    match claims.aal.as_deref() {
+        Some("aal2") => Some(Aal::Aal2),
+        Some("aal1") => Some(Aal::Aal1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(claims: &str) -> String {
+        format!("eyJhbGciOiJIUzI1NiJ9.{}.signature", URL_SAFE_NO_PAD.encode(claims))
+    }
+
+    #[test]
+    fn assurance_level_is_read_from_the_token() {
+        assert_eq!(token_aal(&token(r#"{"sub":"8c1e","aal":"aal2"}"#)), Some(Aal::Aal2));
+        assert_eq!(token_aal(&token(r#"{"sub":"8c1e","aal":"aal1"}"#)), Some(Aal::Aal1));
+        assert_eq!(token_aal(&token(r#"{"sub":"8c1e"}"#)), None);
+        assert_eq!(token_aal("not a token"), None);
+    }
+}
@@ -1,3 +1,10 @@
+pub mod admin;
 pub mod auth_context;
+pub mod fragment;
+pub mod local;
+pub mod mfa;
 pub mod model;
+pub mod permissions;
+pub mod pkce;
+pub mod redirect;
 pub mod views;
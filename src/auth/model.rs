@@ -9,27 +9,61 @@ pub struct User {
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
     pub last_sign_in_at: Option<String>,
+    /// Set while the account is disabled; only returned to admins.
+    pub banned_until: Option<String>,
     pub app_metadata: Option<AppMetadata>,
     pub user_metadata: Option<UserMetadata>,
+    pub factors: Option<Vec<Factor>>,
+}
+
+/// A second factor registered on the account. Enrollment adds it unverified;
+/// only once a code from it has been verified does sign-in ask for one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Factor {
+    pub id: String,
+    pub friendly_name: Option<String>,
+    pub factor_type: String,
+    pub status: String,
+}
+
+impl Factor {
+    pub fn is_verified_totp(&self) -> bool {
+        self.factor_type == "totp" && self.status == "verified"
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AppMetadata {
     pub provider: Option<String>,
     pub providers: Option<Vec<String>>,
+    /// `viewer`, `editor` or `admin`; see `auth::permissions::Role`.
+    #[serde(default)]
+    pub role: Option<String>,
 }
 
+// Unset fields are left out, since GoTrue merges `data` into the stored metadata
+// and a null would erase the value there.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct UserMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub avatar_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub email_verified: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub full_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub phone_verified: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub picture: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub provider_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sub: Option<String>,
 }
 
@@ -43,14 +77,6 @@ pub struct Session {
     pub user: User,
 }
 
-// Local session for when we don't get a proper Supabase session
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct LocalSession {
-    pub user: User,
-    pub created_at: i64, // timestamp when session was created
-    pub expires_at: i64, // when this local session expires (24 hours default)
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AuthResponse {
     pub user: Option<User>,
@@ -80,17 +106,136 @@ pub struct UpdatePasswordRequest {
     pub password: String,
 }
 
+/// Asks GoTrue to email a magic link and a one-time code.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OtpRequest {
+    pub email: String,
+    /// Only existing accounts may sign in this way; sign-ups go through the register form.
+    pub create_user: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerifyOtpRequest {
+    pub email: String,
+    pub token: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Exchanges the code from an OAuth callback, proven by the PKCE verifier.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PkceTokenRequest {
+    pub auth_code: String,
+    pub code_verifier: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+// Only the fields that are set are changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct UpdateUserRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<UserMetadata>,
 }
 
+/// Shortest password Supabase Auth accepts by default.
+pub const MIN_PASSWORD_LENGTH: usize = 6;
+
+impl UpdatePasswordRequest {
+    /// A new password typed twice; both entries must match.
+    pub fn new(password: String, confirmation: &str) -> Result<Self, String> {
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(format!("Password must be at least {} characters long", MIN_PASSWORD_LENGTH));
+        }
+        if password != confirmation {
+            return Err("Passwords do not match".to_string());
+        }
+        Ok(Self { password })
+    }
+}
+
+/// Largest avatar image accepted, in bytes.
+pub const MAX_AVATAR_BYTES: f64 = 2.0 * 1024.0 * 1024.0;
+
+/// An avatar must be an image of at most `MAX_AVATAR_BYTES`.
+pub fn check_avatar(content_type: &str, size: f64) -> Result<(), String> {
+    if !content_type.starts_with("image/") {
+        return Err("Choose an image file for your avatar".to_string());
+    }
+    if size > MAX_AVATAR_BYTES {
+        return Err("Avatar images can be at most 2 MB".to_string());
+    }
+    Ok(())
+}
+
+/// Digits in the one-time code of a sign-in email.
+pub const OTP_LENGTH: usize = 6;
+
+/// A typed code without spaces and dashes, if it has the right number of digits.
+fn one_time_code(code: &str) -> Option<String> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+    (code.len() == OTP_LENGTH && code.chars().all(|c| c.is_ascii_digit())).then_some(code)
+}
+
+impl VerifyOtpRequest {
+    /// A code typed from the sign-in email; spaces and dashes are ignored.
+    pub fn email(email: String, code: &str) -> Result<Self, String> {
+        let token = one_time_code(code)
+            .ok_or_else(|| format!("Enter the {}-digit code from the email", OTP_LENGTH))?;
+        Ok(Self { email, token, kind: "email".to_string() })
+    }
+}
+
+/// Adds a TOTP factor to the signed-in user.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct AuthError {
-    pub message: String,
-    pub error_description: Option<String>,
+pub struct EnrollFactorRequest {
+    pub factor_type: String,
+}
+
+impl EnrollFactorRequest {
+    pub fn totp() -> Self {
+        Self { factor_type: "totp".to_string() }
+    }
+}
+
+/// A newly enrolled, still unverified factor and what authenticator apps need to add it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnrolledFactor {
+    pub id: String,
+    pub totp: TotpSetup,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TotpSetup {
+    /// SVG data URL of a QR code for the `uri`
+    pub qr_code: String,
+    /// The secret, for typing into the app by hand
+    pub secret: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FactorChallenge {
+    pub id: String,
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerifyFactorRequest {
+    pub challenge_id: String,
+    pub code: String,
+}
+
+/// A code typed from an authenticator app; spaces and dashes are ignored.
+pub fn totp_code(code: &str) -> Result<String, String> {
+    one_time_code(code).ok_or_else(|| format!("Enter the {}-digit code from your authenticator app", OTP_LENGTH))
 }
 
 impl User {
@@ -106,6 +251,14 @@ impl User {
         self.email.clone()
     }
 
+    /// When the user last signed in, as `YYYY-MM-DD HH:MM` UTC.
+    pub fn last_sign_in(&self) -> Option<String> {
+        let signed_in = self.last_sign_in_at.as_deref()?;
+        chrono::DateTime::parse_from_rfc3339(signed_in)
+            .ok()
+            .map(|time| time.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M UTC").to_string())
+    }
+
     pub fn avatar_url(&self) -> Option<String> {
         self.user_metadata
             .as_ref()
@@ -114,6 +267,18 @@ impl User {
                 .as_ref()
                 .and_then(|metadata| metadata.picture.clone()))
     }
+
+    /// Whether an admin has disabled the account, i.e. it is banned past `now`.
+    pub fn is_disabled(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.banned_until.as_deref()
+            .and_then(|until| chrono::DateTime::parse_from_rfc3339(until).ok())
+            .is_some_and(|until| until > now)
+    }
+
+    /// The TOTP factor sign-in asks a code from, once one is verified.
+    pub fn totp_factor(&self) -> Option<&Factor> {
+        self.factors.as_ref()?.iter().find(|factor| factor.is_verified_totp())
+    }
 }
 
 impl Default for SignInRequest {
@@ -159,3 +324,81 @@ impl Default for UserMetadata {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_password_must_be_long_enough_and_confirmed() {
+        assert!(UpdatePasswordRequest::new("secret".to_string(), "secret").is_ok());
+        assert_eq!(
+            UpdatePasswordRequest::new("short".to_string(), "short").unwrap_err(),
+            "Password must be at least 6 characters long"
+        );
+        assert_eq!(
+            UpdatePasswordRequest::new("secret1".to_string(), "secret2").unwrap_err(),
+            "Passwords do not match"
+        );
+    }
+
+    #[test]
+    fn profile_updates_only_send_what_changed() {
+        let request = UpdateUserRequest {
+            data: Some(UserMetadata { full_name: Some("Ada".to_string()), ..Default::default() }),
+            ..Default::default()
+        };
+        assert_eq!(serde_json::to_string(&request).unwrap(), r#"{"data":{"full_name":"Ada"}}"#);
+    }
+
+    #[test]
+    fn authenticator_codes_are_six_digits() {
+        assert_eq!(totp_code(" 123 456 ").unwrap(), "123456");
+        assert!(totp_code("12345").is_err());
+        assert!(totp_code("abcdef").is_err());
+    }
+
+    #[test]
+    fn only_verified_totp_factors_are_asked_for() {
+        let user: User = serde_json::from_value(serde_json::json!({
+            "id": "8c1e",
+            "email": "ada@example.com",
+            "factors": [
+                { "id": "a", "factor_type": "totp", "status": "unverified" },
+                { "id": "b", "factor_type": "totp", "status": "verified" },
+            ],
+        })).unwrap();
+        assert_eq!(user.totp_factor().map(|factor| factor.id.as_str()), Some("b"));
+    }
+
+    #[test]
+    fn accounts_are_disabled_until_the_ban_ends() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-10-18T12:00:00Z").unwrap().to_utc();
+        let banned = |until: Option<&str>| -> User {
+            serde_json::from_value(serde_json::json!({
+                "id": "8c1e",
+                "email": "ada@example.com",
+                "banned_until": until,
+            })).unwrap()
+        };
+        assert!(banned(Some("2126-09-24T12:00:00Z")).is_disabled(now));
+        assert!(!banned(Some("2026-10-01T12:00:00Z")).is_disabled(now));
+        assert!(!banned(None).is_disabled(now));
+    }
+
+    #[test]
+    fn avatars_must_be_small_images() {
+        assert!(check_avatar("image/png", 1024.0).is_ok());
+        assert!(check_avatar("application/pdf", 1024.0).is_err());
+        assert!(check_avatar("image/png", MAX_AVATAR_BYTES + 1.0).is_err());
+    }
+
+    #[test]
+    fn one_time_code_must_be_six_digits() {
+        let request = VerifyOtpRequest::email("a@b.c".to_string(), " 123 456 ").unwrap();
+        assert_eq!(request.token, "123456");
+        assert_eq!(request.kind, "email");
+        assert!(VerifyOtpRequest::email("a@b.c".to_string(), "12345").is_err());
+        assert!(VerifyOtpRequest::email("a@b.c".to_string(), "12345a").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::auth::model::User;
+
+/// Role granted to an account through `app_metadata.role`, which only the
+/// service role can set, so users cannot promote themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Viewer,
+    Editor,
+    Admin,
+}
+
+/// Something a role may or may not do in the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Open the editor and read drafts.
+    ViewEditor,
+    /// Create and change projects, areas, content and their links.
+    Edit,
+    /// Delete projects and areas.
+    Delete,
+    /// See and manage other accounts.
+    ManageUsers,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Viewer, Role::Editor, Role::Admin];
+
+    /// Accounts without a recognised role can look but not touch.
+    pub fn of(user: &User) -> Self {
+        user.app_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.role.as_deref())
+            .and_then(Self::parse)
+            .unwrap_or(Role::Viewer)
+    }
+
+    /// Exact lowercase names only, as `public.app_role()` and the `admin-users` function compare them.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "viewer" => Some(Role::Viewer),
+            "editor" => Some(Role::Editor),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Editor => "editor",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::ViewEditor => true,
+            Permission::Edit => *self >= Role::Editor,
+            Permission::Delete | Permission::ManageUsers => *self == Role::Admin,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::model::AppMetadata;
+
+    fn user_with_role(role: Option<&str>) -> User {
+        User {
+            id: "user".to_string(),
+            email: "user@example.com".to_string(),
+            email_confirmed_at: None,
+            phone: None,
+            created_at: None,
+            updated_at: None,
+            last_sign_in_at: None,
+            banned_until: None,
+            app_metadata: Some(AppMetadata {
+                provider: None,
+                providers: None,
+                role: role.map(str::to_string),
+            }),
+            user_metadata: None,
+            factors: None,
+        }
+    }
+
+    #[test]
+    fn role_is_read_from_app_metadata() {
+        assert_eq!(Role::of(&user_with_role(Some("admin"))), Role::Admin);
+        assert_eq!(Role::of(&user_with_role(Some("editor"))), Role::Editor);
+        // The database treats any other spelling as a viewer, so the UI must too
+        assert_eq!(Role::of(&user_with_role(Some("Editor"))), Role::Viewer);
+        assert_eq!(Role::of(&user_with_role(Some(" admin"))), Role::Viewer);
+        assert_eq!(Role::of(&user_with_role(Some("owner"))), Role::Viewer);
+        assert_eq!(Role::of(&user_with_role(None)), Role::Viewer);
+    }
+
+    #[test]
+    fn permissions_grow_with_the_role() {
+        assert!(Role::Viewer.can(Permission::ViewEditor));
+        assert!(!Role::Viewer.can(Permission::Edit));
+        assert!(Role::Editor.can(Permission::Edit));
+        assert!(!Role::Editor.can(Permission::Delete));
+        assert!(Role::Admin.can(Permission::Delete));
+        assert!(Role::Admin.can(Permission::ManageUsers));
+    }
+}
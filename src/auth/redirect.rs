@@ -0,0 +1,40 @@
+/// Query parameter of the login page naming the page to return to.
+pub const RETURN_PARAM: &str = "redirect";
+
+/// Where signed-in users land when no return URL was given.
+pub const DEFAULT_RETURN_PATH: &str = "/editor";
+
+/// Login page URL that returns to `return_to` after signing in.
+pub fn login_url(login_path: &str, return_to: &str) -> String {
+    match serde_urlencoded::to_string([(RETURN_PARAM, return_to)]) {
+        Ok(query) => format!("{}?{}", login_path, query),
+        Err(_) => login_path.to_string(),
+    }
+}
+
+/// The page to go to after signing in. Only paths on this site are accepted,
+/// so a crafted link cannot send the user elsewhere.
+pub fn return_path(param: Option<String>) -> String {
+    match param {
+        Some(path) if path.starts_with('/') && !path.starts_with("//") && !path.starts_with("/\\") => path,
+        _ => DEFAULT_RETURN_PATH.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn login_url_encodes_the_return_path() {
+        assert_eq!(login_url("/login", "/editor/3?tab=a&b"), "/login?redirect=%2Feditor%2F3%3Ftab%3Da%26b");
+    }
+
+    #[test]
+    fn return_path_only_accepts_local_paths() {
+        assert_eq!(return_path(Some("/editor/3".to_string())), "/editor/3");
+        assert_eq!(return_path(Some("//evil.example".to_string())), DEFAULT_RETURN_PATH);
+        assert_eq!(return_path(Some("https://evil.example".to_string())), DEFAULT_RETURN_PATH);
+        assert_eq!(return_path(None), DEFAULT_RETURN_PATH);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::auth::auth_context::use_auth;
+use crate::auth::fragment::FragmentTokens;
+use crate::auth::redirect::DEFAULT_RETURN_PATH;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::{hooks::{use_location, use_navigate, use_query_map}, NavigateOptions};
+
+/// Where magic links and OAuth providers land. OAuth sends `?code=` to exchange with the
+/// stored PKCE verifier, magic links send the tokens in the URL fragment. Either way the
+/// user moves on to the editor, replacing the callback URL in the history.
+#[component]
+pub fn AuthCallback() -> impl IntoView {
+    let auth = use_auth();
+    let navigate = use_navigate();
+    let location = use_location();
+    let query = use_query_map().get_untracked();
+    let (link_error, set_link_error) = signal(None::<String>);
+
+    let finish = move |signed_in: bool| {
+        if signed_in {
+            navigate(DEFAULT_RETURN_PATH, NavigateOptions { replace: true, ..Default::default() });
+        } else {
+            set_link_error.set(auth.error.0.get_untracked());
+        }
+    };
+
+    if let Some(error) = query.get("error_description").or_else(|| query.get("error")) {
+        set_link_error.set(Some(error));
+    } else if let Some(code) = query.get("code") {
+        let auth = auth.clone();
+        spawn_local(async move { finish(auth.exchange_code(code).await) });
+    } else {
+        let fragment = location.hash.get_untracked();
+        let now = js_sys::Date::now() as i64 / 1000;
+        match FragmentTokens::parse(&fragment, now) {
+            Ok(tokens) => {
+                let auth = auth.clone();
+                spawn_local(async move { finish(auth.sign_in_with_link(tokens).await) });
+            }
+            Err(err) => set_link_error.set(Some(err)),
+        }
+    }
+
+    view! {
+        <div class="w-full max-w-md mx-auto">
+            {move || match link_error.get() {
+                Some(error) => view! {
+                    <div class="text-center space-y-6">
+                        <h2 class="text-2xl font-bold text-gray-900">Sign-In Failed</h2>
+                        <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                            {error}
+                        </div>
+                        <a href="/login" class="text-sm text-blue-600 hover:text-blue-500">
+                            "← Back to sign in"
+                        </a>
+                    </div>
+                }.into_any(),
+                None => view! {
+                    <p class="text-center text-sm text-gray-600">"Signing you in..."</p>
+                }.into_any(),
+            }}
+        </div>
+    }
+}
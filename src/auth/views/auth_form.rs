@@ -1,10 +1,12 @@
-use crate::auth::views::{login_form::LoginForm, register_form::RegisterForm};
+use crate::auth::views::{login_form::LoginForm, magic_link_form::MagicLinkForm, oauth_buttons::OAuthButtons, register_form::RegisterForm};
 use leptos::prelude::*;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum AuthFormMode {
     Login,
     Register,
+    /// Passwordless sign-in with an emailed link or one-time code.
+    MagicLink,
 }
 
 #[component]
@@ -25,6 +27,18 @@ pub fn AuthForm(
                         <LoginForm
                             show_register_link=show_toggle
                         />
+
+                        <OAuthButtons />
+
+                        <div class="mt-6 text-center">
+                            <button
+                                type="button"
+                                class="text-sm text-blue-600 hover:text-blue-500"
+                                on:click=move |_| set_current_mode.set(AuthFormMode::MagicLink)
+                            >
+                                "Sign in with an email link instead"
+                            </button>
+                        </div>
                         
                         {move || {
                             if show_toggle {
@@ -68,6 +82,20 @@ pub fn AuthForm(
                             }
                         }}
                     }.into_any(),
+
+                    AuthFormMode::MagicLink => view! {
+                        <MagicLinkForm />
+
+                        <div class="mt-6 text-center">
+                            <button
+                                type="button"
+                                class="text-sm text-blue-600 hover:text-blue-500"
+                                on:click=move |_| set_current_mode.set(AuthFormMode::Login)
+                            >
+                                "Sign in with a password instead"
+                            </button>
+                        </div>
+                    }.into_any(),
                 }
             }}
         </div>
@@ -0,0 +1,182 @@
+use crate::auth::auth_context::use_auth;
+use crate::auth::model::VerifyOtpRequest;
+use crate::auth::redirect::{return_path, RETURN_PARAM};
+use crate::ui::form::simple_form_input::SimpleFormInput;
+use crate::ui::button::{Button, ButtonVariant};
+use leptos::prelude::*;
+use leptos::{ev, task::spawn_local};
+use leptos_router::hooks::{use_navigate, use_query_map};
+
+/// Passwordless sign-in: emails a magic link and a one-time code, then accepts
+/// the code here. The link itself lands on the auth callback route.
+#[component]
+pub fn MagicLinkForm() -> impl IntoView {
+    let auth = use_auth();
+    let navigate = use_navigate();
+    let query = use_query_map();
+    let (email, set_email) = signal(String::new());
+    let (code, set_code) = signal(String::new());
+    let (is_sent, set_is_sent) = signal(false);
+    let (is_submitting, set_is_submitting) = signal(false);
+
+    Effect::new(move |_| {
+        if auth.is_authenticated.0.get() && !auth.is_loading.0.get() {
+            let return_to = return_path(query.with_untracked(|query| query.get(RETURN_PARAM)));
+            navigate(&return_to, Default::default());
+        }
+    });
+
+    let send_email = {
+        let auth = auth.clone();
+        move || {
+            let email_val = email.get_untracked();
+            if email_val.is_empty() {
+                auth.error.1.set(Some("Please enter your email address".to_string()));
+                return;
+            }
+
+            set_is_submitting.set(true);
+            let auth = auth.clone();
+            spawn_local(async move {
+                if auth.send_otp(email_val).await {
+                    set_is_sent.set(true);
+                }
+                set_is_submitting.set(false);
+            });
+        }
+    };
+
+    let on_send = {
+        let send_email = send_email.clone();
+        move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+            send_email();
+        }
+    };
+
+    let on_verify = {
+        let auth = auth.clone();
+        move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+
+            let request = match VerifyOtpRequest::email(email.get(), &code.get()) {
+                Ok(request) => request,
+                Err(err) => {
+                    auth.error.1.set(Some(err));
+                    return;
+                }
+            };
+
+            set_is_submitting.set(true);
+            let auth = auth.clone();
+            spawn_local(async move {
+                auth.verify_otp(request).await;
+                set_is_submitting.set(false);
+            });
+        }
+    };
+
+    view! {
+        <div class="w-full max-w-md mx-auto">
+            {move || {
+                let error = auth.error.0.get().map(|error| view! {
+                    <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                        {error}
+                    </div>
+                });
+                if is_sent.get() {
+                    let on_verify = on_verify.clone();
+                    let send_email = send_email.clone();
+                    view! {
+                        <form on:submit=on_verify class="space-y-6">
+                            <div>
+                                <h2 class="text-2xl font-bold text-gray-900 mb-2">Check Your Email</h2>
+                                <p class="text-sm text-gray-600 mb-6">
+                                    {format!("We sent a sign-in link and a code to {}. Open the link, or enter the code here.", email.get_untracked())}
+                                </p>
+                            </div>
+
+                            {error}
+
+                            <SimpleFormInput
+                                id="otp".to_string()
+                                label="Code".to_string()
+                                input_type="text".to_string()
+                                value=code
+                                on_input=Callback::new(move |val| set_code.set(val))
+                                placeholder="123456".to_string()
+                                required=true
+                            />
+
+                            <Button
+                                type_="submit".to_string()
+                                variant=ButtonVariant::Primary
+                                disabled=is_submitting.get() || auth.is_loading.0.get()
+                                class="w-full".to_string()
+                                on_click=move |_| {}
+                            >
+                                {move || {
+                                    if is_submitting.get() || auth.is_loading.0.get() {
+                                        "Signing in..."
+                                    } else {
+                                        "Sign In"
+                                    }
+                                }}
+                            </Button>
+
+                            <div class="text-center">
+                                <button
+                                    type="button"
+                                    class="text-sm text-blue-600 hover:text-blue-500"
+                                    on:click=move |_| send_email()
+                                >
+                                    "Send another code"
+                                </button>
+                            </div>
+                        </form>
+                    }.into_any()
+                } else {
+                    let on_send = on_send.clone();
+                    view! {
+                        <form on:submit=on_send class="space-y-6">
+                            <div>
+                                <h2 class="text-2xl font-bold text-gray-900 mb-2">Sign In with Email</h2>
+                                <p class="text-sm text-gray-600 mb-6">
+                                    "We'll email you a sign-in link and a one-time code."
+                                </p>
+                            </div>
+
+                            {error}
+
+                            <SimpleFormInput
+                                id="email".to_string()
+                                label="Email".to_string()
+                                input_type="email".to_string()
+                                value=email
+                                on_input=Callback::new(move |val| set_email.set(val))
+                                placeholder="Enter your email".to_string()
+                                required=true
+                            />
+
+                            <Button
+                                type_="submit".to_string()
+                                variant=ButtonVariant::Primary
+                                disabled=is_submitting.get() || auth.is_loading.0.get()
+                                class="w-full".to_string()
+                                on_click=move |_| {}
+                            >
+                                {move || {
+                                    if is_submitting.get() || auth.is_loading.0.get() {
+                                        "Sending..."
+                                    } else {
+                                        "Email Me a Link"
+                                    }
+                                }}
+                            </Button>
+                        </form>
+                    }.into_any()
+                }
+            }}
+        </div>
+    }
+}
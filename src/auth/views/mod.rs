@@ -1,5 +1,13 @@
+pub mod auth_callback;
 pub mod auth_form;
 pub mod login_form;
+pub mod magic_link_form;
+pub mod oauth_buttons;
+pub mod profile_page;
 pub mod register_form;
 pub mod reset_password_form;
 pub mod protected_route;
+pub mod totp_challenge;
+pub mod totp_enrollment;
+pub mod update_password_form;
+pub mod users_page;
@@ -0,0 +1,57 @@
+use crate::auth::auth_context::use_auth;
+use crate::shared::environment::use_environment;
+use crate::ui::button::{Button, ButtonVariant};
+use leptos::prelude::*;
+
+/// Button label for a provider id: `github` -> `GitHub`.
+fn provider_label(provider: &str) -> String {
+    match provider {
+        "github" => "GitHub".to_string(),
+        "gitlab" => "GitLab".to_string(),
+        "linkedin_oidc" => "LinkedIn".to_string(),
+        _ => {
+            let mut chars = provider.chars();
+            chars.next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// One sign-in button per OAuth provider enabled in the environment's profile.
+#[component]
+pub fn OAuthButtons() -> impl IntoView {
+    let auth = use_auth();
+    let providers = use_environment().oauth_providers;
+
+    (!providers.is_empty()).then(|| view! {
+        <div class="mt-6 space-y-3">
+            <p class="text-center text-sm text-gray-500">"or continue with"</p>
+            {providers.into_iter().map(|provider| {
+                let auth = auth.clone();
+                let label = provider_label(&provider);
+                view! {
+                    <Button
+                        variant=ButtonVariant::Secondary
+                        class="w-full".to_string()
+                        on_click=move |_| auth.sign_in_with_provider(&provider)
+                    >
+                        {label}
+                    </Button>
+                }
+            }).collect_view()}
+        </div>
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_labels() {
+        assert_eq!(provider_label("github"), "GitHub");
+        assert_eq!(provider_label("google"), "Google");
+        assert_eq!(provider_label("linkedin_oidc"), "LinkedIn");
+    }
+}
@@ -0,0 +1,223 @@
+use crate::auth::auth_context::use_auth;
+use crate::auth::model::{UpdatePasswordRequest, UpdateUserRequest, UserMetadata};
+use crate::ui::form::simple_form_input::SimpleFormInput;
+use crate::ui::button::{Button, ButtonVariant};
+use crate::ui::error::{ErrorMessage, FieldError, SuccessMessage};
+use leptos::prelude::*;
+use leptos::{ev, task::spawn_local};
+
+/// The signed-in user's own account: name and avatar, password, last sign-in.
+/// Each section reports its own result, since they save independently.
+#[component]
+pub fn ProfilePage() -> impl IntoView {
+    let auth = use_auth();
+    let user = auth.user.0;
+    let (full_name, set_full_name) = signal(
+        user.get_untracked()
+            .and_then(|user| user.user_metadata)
+            .and_then(|metadata| metadata.full_name)
+            .unwrap_or_default(),
+    );
+    let (password, set_password) = signal(String::new());
+    let (confirm_password, set_confirm_password) = signal(String::new());
+    let (profile_error, set_profile_error) = signal(None::<String>);
+    let (profile_saved, set_profile_saved) = signal(None::<String>);
+    let (password_error, set_password_error) = signal(None::<String>);
+    let (password_saved, set_password_saved) = signal(false);
+    let (is_saving, set_is_saving) = signal(false);
+
+    // The context reports failures through its shared error signal
+    let finish_profile = move |saved: bool, message: &str| {
+        if saved {
+            set_profile_saved.set(Some(message.to_string()));
+        } else {
+            set_profile_error.set(auth.error.0.get_untracked());
+        }
+        set_is_saving.set(false);
+    };
+
+    let on_save_name = {
+        let auth = auth.clone();
+        move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+            if is_saving.get_untracked() {
+                return;
+            }
+            set_profile_error.set(None);
+            set_profile_saved.set(None);
+
+            let name = full_name.get().trim().to_string();
+            if name.is_empty() {
+                set_profile_error.set(Some("Please enter your name".to_string()));
+                return;
+            }
+
+            set_is_saving.set(true);
+            let auth = auth.clone();
+            spawn_local(async move {
+                let request = UpdateUserRequest {
+                    data: Some(UserMetadata { full_name: Some(name), ..Default::default() }),
+                    ..Default::default()
+                };
+                finish_profile(auth.update_user(request).await, "Name saved");
+            });
+        }
+    };
+
+    let on_avatar = {
+        let auth = auth.clone();
+        move |ev: ev::Targeted<ev::Event, web_sys::HtmlInputElement>| {
+            let input = ev.target();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // Picking the same file again should upload it again
+            input.set_value("");
+            set_profile_error.set(None);
+            set_profile_saved.set(None);
+
+            set_is_saving.set(true);
+            let auth = auth.clone();
+            spawn_local(async move {
+                finish_profile(auth.upload_avatar(file).await, "Avatar updated");
+            });
+        }
+    };
+
+    let on_change_password = {
+        let auth = auth.clone();
+        move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+            if is_saving.get_untracked() {
+                return;
+            }
+            set_password_error.set(None);
+            set_password_saved.set(false);
+
+            let request = match UpdatePasswordRequest::new(password.get(), &confirm_password.get()) {
+                Ok(request) => request,
+                Err(err) => {
+                    set_password_error.set(Some(err));
+                    return;
+                }
+            };
+
+            set_is_saving.set(true);
+            let auth = auth.clone();
+            spawn_local(async move {
+                if auth.change_password(request).await {
+                    set_password.set(String::new());
+                    set_confirm_password.set(String::new());
+                    set_password_saved.set(true);
+                } else {
+                    set_password_error.set(auth.error.0.get_untracked());
+                }
+                set_is_saving.set(false);
+            });
+        }
+    };
+
+    view! {
+        <main class="min-h-screen bg-gray-50 py-12 px-4">
+            <div class="w-full max-w-md mx-auto space-y-10">
+                <a href="/editor" class="text-sm text-blue-600 hover:text-blue-500">"← Back to the editor"</a>
+
+                <section class="flex items-center gap-4">
+                    {move || match user.get().and_then(|user| user.avatar_url()) {
+                        Some(url) => view! {
+                            <img class="w-20 h-20 rounded-full object-cover" src=url alt="Avatar" />
+                        }.into_any(),
+                        None => view! {
+                            <div class="w-20 h-20 rounded-full bg-gray-200"></div>
+                        }.into_any(),
+                    }}
+                    <div>
+                        <h2 class="text-2xl font-bold text-gray-900">
+                            {move || user.get().map(|user| user.display_name()).unwrap_or_default()}
+                        </h2>
+                        <p class="text-sm text-gray-600">
+                            {move || user.get()
+                                .and_then(|user| user.last_sign_in())
+                                .map(|signed_in| format!("Last signed in {}", signed_in))
+                                .unwrap_or_else(|| "Never signed in".to_string())}
+                        </p>
+                    </div>
+                </section>
+
+                <form on:submit=on_save_name class="space-y-4">
+                    <h3 class="text-lg font-semibold text-gray-900">Profile</h3>
+
+                    {move || profile_error.get().map(|error| view! { <ErrorMessage message=error /> })}
+                    {move || profile_saved.get().map(|message| view! { <SuccessMessage message=message /> })}
+
+                    <SimpleFormInput
+                        id="full_name".to_string()
+                        label="Full Name".to_string()
+                        input_type="text".to_string()
+                        value=full_name
+                        on_input=Callback::new(move |val| set_full_name.set(val))
+                        placeholder="Your name".to_string()
+                        required=true
+                    />
+
+                    <div>
+                        <label for="avatar" class="block text-sm font-medium text-gray-700">Avatar</label>
+                        <input
+                            id="avatar"
+                            type="file"
+                            accept="image/*"
+                            class="mt-1 block w-full text-sm text-gray-700"
+                            disabled=move || is_saving.get()
+                            on:change:target=on_avatar
+                        />
+                    </div>
+
+                    <Button
+                        type_="submit".to_string()
+                        variant=ButtonVariant::Primary
+                        class="w-full".to_string()
+                        on_click=move |_| {}
+                    >
+                        {move || if is_saving.get() { "Saving..." } else { "Save Name" }}
+                    </Button>
+                </form>
+
+                <form on:submit=on_change_password class="space-y-4">
+                    <h3 class="text-lg font-semibold text-gray-900">Change Password</h3>
+
+                    {move || password_saved.get().then(|| view! { <SuccessMessage message="Password changed".to_string() /> })}
+
+                    <SimpleFormInput
+                        id="password".to_string()
+                        label="New Password".to_string()
+                        input_type="password".to_string()
+                        value=password
+                        on_input=Callback::new(move |val| set_password.set(val))
+                        placeholder="Enter a new password".to_string()
+                        required=true
+                    />
+
+                    <SimpleFormInput
+                        id="confirm_password".to_string()
+                        label="Confirm Password".to_string()
+                        input_type="password".to_string()
+                        value=confirm_password
+                        on_input=Callback::new(move |val| set_confirm_password.set(val))
+                        placeholder="Enter it again".to_string()
+                        required=true
+                    />
+                    {move || password_error.get().map(|error| view! { <FieldError error=error /> })}
+
+                    <Button
+                        type_="submit".to_string()
+                        variant=ButtonVariant::Primary
+                        class="w-full".to_string()
+                        on_click=move |_| {}
+                    >
+                        "Change Password"
+                    </Button>
+                </form>
+            </div>
+        </main>
+    }
+}
@@ -1,23 +1,45 @@
 use leptos::prelude::*;
-use leptos_router::hooks::use_navigate;
-use crate::auth::auth_context::use_auth;
+use leptos_router::hooks::{use_location, use_navigate};
+use crate::auth::{auth_context::use_auth, mfa::Aal, permissions::Permission, redirect::login_url};
+use crate::auth::views::totp_enrollment::TotpEnrollment;
 
 #[component]
 pub fn ProtectedRoute(
     #[prop(optional)] redirect_path: Option<&'static str>,
-    children: Children,
+    /// Signed-in users without this permission see a notice instead of the page.
+    #[prop(optional)] permission: Option<Permission>,
+    /// Require a second factor verified in this session (`aal2`). Users with a TOTP
+    /// factor are sent back to sign-in for a code; users without one set it up here.
+    #[prop(optional)] require_aal2: bool,
+    children: ChildrenFn,
 ) -> impl IntoView {
     let auth = use_auth();
+    let allowed = {
+        let auth = auth.clone();
+        move || permission.is_none_or(|permission| auth.can(permission))
+    };
+    // Memos, so a refreshed session at the same level does not re-render the page
+    let verified = Memo::new({
+        let auth = auth.clone();
+        move |_| !require_aal2 || auth.aal() == Some(Aal::Aal2)
+    });
+    let enroll = Memo::new({
+        let auth = auth.clone();
+        move |_| auth.is_authenticated() && !auth.needs_mfa()
+    });
     let navigate = use_navigate();
+    let location = use_location();
     let redirect = redirect_path.unwrap_or("/login");
 
-    // Effect to handle redirects when authentication state changes
+    // Effect to handle redirects when authentication state changes,
+    // e.g. when the session could not be refreshed; the login page brings the user back here
     Effect::new({
         let navigate = navigate.clone();
-        let redirect = redirect.to_string();
         move |_| {
-            if !auth.is_loading.0.get() && !auth.is_authenticated() {
-                navigate(&redirect, Default::default());
+            let needs_code = require_aal2 && auth.needs_mfa();
+            if !auth.is_loading.0.get() && (!auth.is_authenticated() || needs_code) {
+                let return_to = format!("{}{}", location.pathname.get_untracked(), location.search.get_untracked());
+                navigate(&login_url(redirect, &return_to), Default::default());
             }
         }
     });
@@ -38,7 +60,30 @@ pub fn ProtectedRoute(
     //     );
        
     // });
+    let page = move || {
+        let children = children.clone();
+        view! {
+            <Show
+                when=move || verified.get()
+                fallback=move || enroll.get().then(|| view! { <TotpEnrollment /> })
+            >
+                {children()}
+            </Show>
+        }
+    };
+
     view! {              
-        {children()}
+        <Show
+            when=allowed
+            fallback=|| view! {
+                <div class="min-h-screen flex items-center justify-center bg-gray-50 px-4">
+                    <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                        "Your account does not have access to this page. Ask an admin to grant you a role."
+                    </div>
+                </div>
+            }
+        >
+            {page()}
+        </Show>
     }
 }
\ No newline at end of file
@@ -0,0 +1,92 @@
+use crate::auth::auth_context::use_auth;
+use crate::auth::model::totp_code;
+use crate::ui::form::simple_form_input::SimpleFormInput;
+use crate::ui::button::{Button, ButtonVariant};
+use leptos::prelude::*;
+use leptos::{ev, task::spawn_local};
+
+/// Second sign-in step for users with a verified TOTP factor: asks for the
+/// current code from their authenticator app.
+#[component]
+pub fn TotpChallenge() -> impl IntoView {
+    let auth = use_auth();
+    let (code, set_code) = signal(String::new());
+    let (is_submitting, set_is_submitting) = signal(false);
+
+    let on_submit = {
+        let auth = auth.clone();
+        move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+
+            let Some(factor_id) = auth.user.0.get_untracked()
+                .and_then(|user| user.totp_factor().map(|factor| factor.id.clone()))
+            else {
+                return;
+            };
+            let code = match totp_code(&code.get()) {
+                Ok(code) => code,
+                Err(err) => {
+                    auth.error.1.set(Some(err));
+                    return;
+                }
+            };
+
+            set_is_submitting.set(true);
+            let auth = auth.clone();
+            spawn_local(async move {
+                auth.verify_totp(&factor_id, code).await;
+                set_is_submitting.set(false);
+            });
+        }
+    };
+
+    let on_cancel = {
+        let auth = auth.clone();
+        move |_| {
+            let auth = auth.clone();
+            spawn_local(async move { auth.sign_out().await });
+        }
+    };
+
+    view! {
+        <form on:submit=on_submit class="space-y-6">
+            <div>
+                <h2 class="text-2xl font-bold text-gray-900 mb-2">Two-Factor Authentication</h2>
+                <p class="text-sm text-gray-600 mb-6">
+                    "Enter the code from your authenticator app."
+                </p>
+            </div>
+
+            {move || auth.error.0.get().map(|error| view! {
+                <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                    {error}
+                </div>
+            })}
+
+            <SimpleFormInput
+                id="totp".to_string()
+                label="Code".to_string()
+                input_type="text".to_string()
+                value=code
+                on_input=Callback::new(move |val| set_code.set(val))
+                placeholder="123456".to_string()
+                required=true
+            />
+
+            <Button
+                type_="submit".to_string()
+                variant=ButtonVariant::Primary
+                class="w-full".to_string()
+                on_click=move |_| {}
+            >
+                {move || if is_submitting.get() { "Verifying..." } else { "Verify" }}
+            </Button>
+
+            <div class="text-center">
+                <button type="button" class="text-sm text-blue-600 hover:text-blue-500" on:click=on_cancel>
+                    "Sign in with a different account"
+                </button>
+            </div>
+        </form>
+    }
+}
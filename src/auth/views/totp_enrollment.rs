@@ -0,0 +1,100 @@
+use crate::auth::auth_context::use_auth;
+use crate::auth::model::{totp_code, EnrolledFactor};
+use crate::ui::form::simple_form_input::SimpleFormInput;
+use crate::ui::button::{Button, ButtonVariant};
+use leptos::prelude::*;
+use leptos::{ev, task::spawn_local};
+
+/// Sets up TOTP for a signed-in user without a second factor: shows the QR code
+/// and secret for an authenticator app, then verifies a first code from it.
+#[component]
+pub fn TotpEnrollment() -> impl IntoView {
+    let auth = use_auth();
+    let factor = RwSignal::new(None::<EnrolledFactor>);
+    let (code, set_code) = signal(String::new());
+    let (is_submitting, set_is_submitting) = signal(false);
+
+    {
+        let auth = auth.clone();
+        spawn_local(async move {
+            if let Some(enrolled) = auth.enroll_totp().await {
+                factor.set(Some(enrolled));
+            }
+        });
+    }
+
+    let on_submit = {
+        let auth = auth.clone();
+        move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+
+            let Some(enrolled) = factor.get_untracked() else {
+                return;
+            };
+            let code = match totp_code(&code.get()) {
+                Ok(code) => code,
+                Err(err) => {
+                    auth.error.1.set(Some(err));
+                    return;
+                }
+            };
+
+            set_is_submitting.set(true);
+            let auth = auth.clone();
+            spawn_local(async move {
+                auth.verify_totp(&enrolled.id, code).await;
+                set_is_submitting.set(false);
+            });
+        }
+    };
+
+    view! {
+        <div class="min-h-screen flex items-center justify-center bg-gray-50 py-12 px-4">
+            <form on:submit=on_submit class="w-full max-w-md space-y-6">
+                <div>
+                    <h2 class="text-2xl font-bold text-gray-900 mb-2">Set Up Two-Factor Authentication</h2>
+                    <p class="text-sm text-gray-600 mb-6">
+                        "The editor needs a code from an authenticator app at every sign-in. Scan the QR code with the app, or type in the secret, then enter the code it shows."
+                    </p>
+                </div>
+
+                {move || auth.error.0.get().map(|error| view! {
+                    <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                        {error}
+                    </div>
+                })}
+
+                {move || match factor.get() {
+                    Some(enrolled) => view! {
+                        <div class="flex flex-col items-center gap-2">
+                            <img class="w-48 h-48" src=enrolled.totp.qr_code alt="QR code for your authenticator app" />
+                            <code class="text-sm text-gray-700 break-all">{enrolled.totp.secret}</code>
+                        </div>
+                    }.into_any(),
+                    None => view! {
+                        <p class="text-center text-sm text-gray-600">"Preparing..."</p>
+                    }.into_any(),
+                }}
+
+                <SimpleFormInput
+                    id="totp".to_string()
+                    label="Code".to_string()
+                    input_type="text".to_string()
+                    value=code
+                    on_input=Callback::new(move |val| set_code.set(val))
+                    placeholder="123456".to_string()
+                    required=true
+                />
+
+                <Button
+                    type_="submit".to_string()
+                    variant=ButtonVariant::Primary
+                    class="w-full".to_string()
+                    on_click=move |_| {}
+                >
+                    {move || if is_submitting.get() { "Verifying..." } else { "Turn On" }}
+                </Button>
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,158 @@
+use crate::auth::auth_context::{use_auth, UPDATE_PASSWORD_PATH};
+use crate::auth::fragment::FragmentTokens;
+use crate::auth::model::{Session, UpdatePasswordRequest};
+use crate::ui::form::simple_form_input::SimpleFormInput;
+use crate::ui::button::{Button, ButtonVariant};
+use leptos::prelude::*;
+use leptos::{ev, task::spawn_local};
+use leptos_router::{hooks::{use_location, use_navigate}, NavigateOptions};
+
+/// Landing page of the password-recovery email: reads the session tokens from
+/// the URL fragment and lets the user choose a new password, then signs them in.
+#[component]
+pub fn UpdatePasswordForm() -> impl IntoView {
+    let auth = use_auth();
+    let navigate = use_navigate();
+    let location = use_location();
+    let recovery = RwSignal::new(None::<Session>);
+    let (link_error, set_link_error) = signal(None::<String>);
+    let (is_verifying, set_is_verifying) = signal(true);
+    let (password, set_password) = signal(String::new());
+    let (confirm_password, set_confirm_password) = signal(String::new());
+    let (is_submitting, set_is_submitting) = signal(false);
+
+    let fragment = location.hash.get_untracked();
+    let now = js_sys::Date::now() as i64 / 1000;
+    match FragmentTokens::parse(&fragment, now) {
+        Ok(tokens) => {
+            let auth = auth.clone();
+            let navigate = navigate.clone();
+            spawn_local(async move {
+                // Keep the tokens out of the address bar and the history
+                navigate(UPDATE_PASSWORD_PATH, NavigateOptions { replace: true, ..Default::default() });
+                match auth.session_from_link(tokens).await {
+                    Ok(session) => recovery.set(Some(session)),
+                    Err(err) => set_link_error.set(Some(err.to_string())),
+                }
+                set_is_verifying.set(false);
+            });
+        }
+        Err(err) => {
+            set_link_error.set(Some(err));
+            set_is_verifying.set(false);
+        }
+    }
+
+    let on_submit = {
+        let auth = auth.clone();
+        let navigate = navigate.clone();
+        move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+
+            let Some(session) = recovery.get_untracked() else {
+                return;
+            };
+            let request = match UpdatePasswordRequest::new(password.get(), &confirm_password.get()) {
+                Ok(request) => request,
+                Err(err) => {
+                    auth.error.1.set(Some(err));
+                    return;
+                }
+            };
+
+            set_is_submitting.set(true);
+            let auth = auth.clone();
+            let navigate = navigate.clone();
+            spawn_local(async move {
+                if auth.update_password(session, request).await {
+                    navigate("/editor", Default::default());
+                }
+                set_is_submitting.set(false);
+            });
+        }
+    };
+
+    view! {
+        <div class="w-full max-w-md mx-auto">
+            {move || {
+                let on_submit = on_submit.clone();
+                if is_verifying.get() {
+                    view! {
+                        <p class="text-center text-sm text-gray-600">"Checking your reset link..."</p>
+                    }.into_any()
+                } else if let Some(error) = link_error.get() {
+                    view! {
+                        <div class="text-center space-y-6">
+                            <h2 class="text-2xl font-bold text-gray-900">Reset Link Not Valid</h2>
+                            <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                                {error}
+                            </div>
+                            <a href="/reset-password" class="text-sm text-blue-600 hover:text-blue-500">
+                                "Request a new link"
+                            </a>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! {
+                        <form on:submit=on_submit class="space-y-6">
+                            <div>
+                                <h2 class="text-2xl font-bold text-gray-900 mb-2">Choose a New Password</h2>
+                                <p class="text-sm text-gray-600 mb-6">
+                                    {move || recovery.get().map(|session| format!("Signing in as {}.", session.user.email))}
+                                </p>
+                            </div>
+
+                            {move || {
+                                if let Some(error) = auth.error.0.get() {
+                                    view! {
+                                        <div class="bg-red-50 border border-red-200 text-red-700 px-4 py-3 rounded">
+                                            {error}
+                                        </div>
+                                    }.into_any()
+                                } else {
+                                    ().into_any()
+                                }
+                            }}
+
+                            <SimpleFormInput
+                                id="password".to_string()
+                                label="New Password".to_string()
+                                input_type="password".to_string()
+                                value=password
+                                on_input=Callback::new(move |val| set_password.set(val))
+                                placeholder="Enter a new password".to_string()
+                                required=true
+                            />
+
+                            <SimpleFormInput
+                                id="confirm_password".to_string()
+                                label="Confirm Password".to_string()
+                                input_type="password".to_string()
+                                value=confirm_password
+                                on_input=Callback::new(move |val| set_confirm_password.set(val))
+                                placeholder="Enter it again".to_string()
+                                required=true
+                            />
+
+                            <Button
+                                type_="submit".to_string()
+                                variant=ButtonVariant::Primary
+                                disabled=is_submitting.get() || auth.is_loading.0.get()
+                                class="w-full".to_string()
+                                on_click=move |_| {}
+                            >
+                                {move || {
+                                    if is_submitting.get() || auth.is_loading.0.get() {
+                                        "Saving..."
+                                    } else {
+                                        "Save and Sign In"
+                                    }
+                                }}
+                            </Button>
+                        </form>
+                    }.into_any()
+                }
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,203 @@
+use crate::auth::admin::{AdminRequest, UserAdmin};
+use crate::auth::auth_context::{site_url, use_auth, UPDATE_PASSWORD_PATH};
+use crate::auth::model::User;
+use crate::auth::permissions::Role;
+use crate::supabase::{use_supabase, SupabaseError};
+use crate::ui::button::{Button, ButtonVariant};
+use crate::ui::error::{ErrorMessage, SuccessMessage};
+use leptos::prelude::*;
+use leptos::{ev, task::spawn_local};
+
+/// Admin screen for accounts: invite editors, change roles, disable accounts.
+/// Admins cannot change or disable their own account here, so there is always one left.
+#[component]
+pub fn UsersPage() -> impl IntoView {
+    let admin = UserAdmin::new(use_supabase());
+    let current_user_id = use_auth().user.0.get_untracked().map(|user| user.id).unwrap_or_default();
+    let users = RwSignal::new(Vec::<User>::new());
+    let (error, set_error) = signal(None::<String>);
+    let (notice, set_notice) = signal(None::<String>);
+    let (is_loading, set_is_loading) = signal(true);
+    let (invite_email, set_invite_email) = signal(String::new());
+    let (invite_role, set_invite_role) = signal(Role::Editor);
+
+    {
+        let admin = admin.clone();
+        spawn_local(async move {
+            match admin.list().await {
+                Ok(list) => users.set(list),
+                Err(err) => set_error.set(Some(err.to_string())),
+            }
+            set_is_loading.set(false);
+        });
+    }
+
+    // Replace a row with the account as the server returned it
+    let apply = move |result: Result<User, SupabaseError>, message: String| match result {
+        Ok(updated) => {
+            users.update(|users| {
+                if let Some(user) = users.iter_mut().find(|user| user.id == updated.id) {
+                    *user = updated;
+                } else {
+                    users.push(updated);
+                }
+            });
+            set_notice.set(Some(message));
+        }
+        Err(err) => set_error.set(Some(err.to_string())),
+    };
+
+    let on_invite = {
+        let admin = admin.clone();
+        move |ev: ev::SubmitEvent| {
+            ev.prevent_default();
+            set_error.set(None);
+            set_notice.set(None);
+
+            let email = invite_email.get();
+            let request = match AdminRequest::invite(&email, invite_role.get(), site_url(UPDATE_PASSWORD_PATH)) {
+                Ok(request) => request,
+                Err(err) => {
+                    set_error.set(Some(err));
+                    return;
+                }
+            };
+
+            let admin = admin.clone();
+            spawn_local(async move {
+                let result = admin.invite(request).await;
+                if result.is_ok() {
+                    set_invite_email.set(String::new());
+                }
+                apply(result, format!("Invitation sent to {}", email.trim()));
+            });
+        }
+    };
+
+    view! {
+        <div class="bg-white rounded-lg shadow-md p-6 w-full max-w-4xl">
+            <h2 class="text-xl font-semibold mb-4">"Users"</h2>
+
+            {move || error.get().map(|message| view! { <ErrorMessage message=message class="pb-2".to_string() /> })}
+            {move || notice.get().map(|message| view! { <SuccessMessage message=message class="pb-2".to_string() /> })}
+
+            <form on:submit=on_invite class="flex gap-2 items-end mb-6 text-sm">
+                <label class="flex flex-col flex-1">
+                    "Invite by email"
+                    <input
+                        type="email"
+                        class="border border-gray-300 rounded px-2 py-1"
+                        placeholder="name@example.com"
+                        prop:value=invite_email
+                        on:input:target=move |ev| set_invite_email.set(ev.target().value())
+                    />
+                </label>
+                <RoleSelect role=invite_role.into() on_change=Callback::new(move |role| set_invite_role.set(role)) />
+                <Button type_="submit".to_string() variant=ButtonVariant::Primary on_click=move |_| {}>
+                    "Invite"
+                </Button>
+            </form>
+
+            {move || is_loading.get().then(|| view! {
+                <p class="text-sm text-gray-500">"Loading users…"</p>
+            })}
+
+            <table class="w-full text-sm">
+                <thead>
+                    <tr class="text-left text-gray-500">
+                        <th class="py-1">"User"</th>
+                        <th class="py-1">"Role"</th>
+                        <th class="py-1">"Last sign-in"</th>
+                        <th class="py-1">"Status"</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    <For
+                        each=move || users.get()
+                        key=|user| (user.id.clone(), user.updated_at.clone(), user.banned_until.clone())
+                        let:user
+                    >
+                        {
+                            let is_self = user.id == current_user_id;
+                            let disabled = user.is_disabled(chrono::Utc::now());
+                            let user_id = user.id.clone();
+                            let admin = admin.clone();
+                            let on_role = {
+                                let admin = admin.clone();
+                                let user_id = user_id.clone();
+                                Callback::new(move |role: Role| {
+                                    set_error.set(None);
+                                    let admin = admin.clone();
+                                    let user_id = user_id.clone();
+                                    spawn_local(async move {
+                                        apply(admin.set_role(&user_id, role).await, format!("Role changed to {}", role.as_str()));
+                                    });
+                                })
+                            };
+                            let on_toggle = move |_| {
+                                set_error.set(None);
+                                let admin = admin.clone();
+                                let user_id = user_id.clone();
+                                spawn_local(async move {
+                                    let message = if disabled { "Account enabled" } else { "Account disabled" };
+                                    apply(admin.set_disabled(&user_id, !disabled).await, message.to_string());
+                                });
+                            };
+                            view! {
+                                <tr class="border-t border-gray-200">
+                                    <td class="py-2">
+                                        <div>{user.display_name()}</div>
+                                        <div class="text-gray-500">{user.email.clone()}</div>
+                                    </td>
+                                    <td class="py-2">
+                                        {if is_self {
+                                            view! { <span>{Role::of(&user).as_str()}</span> }.into_any()
+                                        } else {
+                                            view! { <RoleSelect role=Signal::stored(Role::of(&user)) on_change=on_role /> }.into_any()
+                                        }}
+                                    </td>
+                                    <td class="py-2">{user.last_sign_in().unwrap_or_else(|| "Never".to_string())}</td>
+                                    <td class="py-2">
+                                        {if disabled {
+                                            "Disabled"
+                                        } else if user.email_confirmed_at.is_none() {
+                                            "Invited"
+                                        } else {
+                                            "Active"
+                                        }}
+                                    </td>
+                                    <td class="py-2 text-right">
+                                        {(!is_self).then(|| view! {
+                                            <button class="text-blue-600 hover:text-blue-500" on:click=on_toggle>
+                                                {if disabled { "Enable" } else { "Disable" }}
+                                            </button>
+                                        })}
+                                    </td>
+                                </tr>
+                            }
+                        }
+                    </For>
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+#[component]
+fn RoleSelect(role: Signal<Role>, on_change: Callback<Role>) -> impl IntoView {
+    view! {
+        <select
+            class="border border-gray-300 rounded px-2 py-1"
+            on:change:target=move |ev| {
+                if let Some(role) = Role::parse(&ev.target().value()) {
+                    on_change.run(role);
+                }
+            }
+        >
+            {Role::ALL.into_iter().map(|option| view! {
+                <option value=option.as_str() selected=move || role.get() == option>{option.as_str()}</option>
+            }).collect::<Vec<_>>()}
+        </select>
+    }
+}
@@ -0,0 +1,525 @@
+use std::sync::RwLock;
+
+use serde::Deserialize;
+use web_sys::{File, Url};
+
+use crate::{
+    areas::model::{ProjectArea, ProjectAreaDto},
+    backend::{Backend, BackendFuture},
+    catalog::model::ProjectAreaLink,
+    content::model::{ProjectContent, ProjectContentDto},
+    projects::model::{lifecycle_changes, Maturity, Project, ProjectDto, ProjectStatus, ProjectTransition},
+    supabase::{Page, SupabaseError},
+};
+
+const SEED_SCHEME: &str = include_str!("../../posts-scheme.json");
+
+#[derive(Debug, Default)]
+struct Tables {
+    areas: Vec<ProjectArea>,
+    projects: Vec<Project>,
+    catalog: Vec<ProjectAreaLink>,
+    content: Vec<ProjectContent>,
+    transitions: Vec<ProjectTransition>,
+    next_id: i64,
+}
+
+impl Tables {
+    fn next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Check the lifecycle changes of a write and log them, as the database trigger does.
+    fn log_transitions(&mut self, before: &Project, after: &ProjectDto, project_id: i32) -> Result<(), SupabaseError> {
+        for change in lifecycle_changes(before, after).map_err(SupabaseError::Request)? {
+            let transition = ProjectTransition {
+                id: self.next_id(),
+                created_at: Some(now()),
+                project_id: project_id as i64,
+                field: change.field,
+                from_value: change.from_value,
+                to_value: change.to_value,
+            };
+            self.transitions.push(transition);
+        }
+        Ok(())
+    }
+}
+
+/// Backend that keeps every table in memory, for running without a network and for tests.
+#[derive(Debug, Default)]
+pub struct InMemoryBackend {
+    tables: RwLock<Tables>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SchemeFile {
+    projects: Vec<SchemeProject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SchemeProject {
+    id: i32,
+    title: String,
+    description: Option<String>,
+    #[serde(default)]
+    technologies: Vec<String>,
+    budget: Option<i64>,
+    status: Option<ProjectStatus>,
+    date_started: Option<String>,
+    date_ended: Option<String>,
+    precision: Option<i32>,
+    #[serde(default)]
+    tools: Vec<String>,
+    client: Option<String>,
+    industry: Option<String>,
+    maturity: Option<Maturity>,
+}
+
+fn not_found(table: &str) -> SupabaseError {
+    SupabaseError::Http {
+        status: 404,
+        resource: table.to_string(),
+        body: None,
+    }
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+impl InMemoryBackend {
+    /// Backend seeded with the projects in `posts-scheme.json`. The file is bundled
+    /// at compile time, so a parse error is a bug and panics rather than seeding nothing.
+    pub fn seeded() -> Self {
+        Self::from_scheme_json(SEED_SCHEME).expect("posts-scheme.json is not a valid seed")
+    }
+
+    /// Seed projects from a `posts-scheme.json` document. Technologies, tools,
+    /// industry and maturity become areas in matching categories, linked through the catalog.
+    pub fn from_scheme_json(json: &str) -> Result<Self, SupabaseError> {
+        let scheme: SchemeFile = serde_json::from_str(json)
+            .map_err(|e| SupabaseError::Deserialize(e.to_string()))?;
+
+        let mut tables = Tables::default();
+        for project in &scheme.projects {
+            tables.next_id = tables.next_id.max(project.id as i64);
+        }
+
+        for project in scheme.projects {
+            let seeded = Project {
+                id: project.id,
+                title: project.title,
+                desc: project.description,
+                created_at: Some(now()),
+                order: Some(project.id),
+                technologies: project.technologies.clone(),
+                budget: project.budget,
+                status: project.status,
+                date_started: project.date_started,
+                date_ended: project.date_ended,
+                precision: project.precision,
+                tools: project.tools.clone(),
+                client: project.client,
+                industry: project.industry.clone(),
+                maturity: project.maturity,
+            };
+            tables.log_transitions(&Project::default(), &seeded.to_dto(), seeded.id)?;
+            tables.projects.push(seeded);
+
+            let tagged = [
+                ("Technologies", project.technologies),
+                ("Tools", project.tools),
+                ("Industry", project.industry.into_iter().collect()),
+                ("Maturity", project.maturity.map(|maturity| maturity.as_str().to_string()).into_iter().collect()),
+            ];
+            for (category, titles) in tagged {
+                for title in titles {
+                    let area_id = match tables.areas.iter().find(|a| a.category == category && a.title == title) {
+                        Some(area) => area.id,
+                        None => {
+                            let id = tables.next_id();
+                            let order = tables.areas.iter().filter(|a| a.category == category).count() as i32 + 1;
+                            tables.areas.push(ProjectArea {
+                                id,
+                                created_at: Some(now()),
+                                title,
+                                category: category.to_string(),
+                                desc: None,
+                                order: Some(order),
+                                format: None,
+                            });
+                            id
+                        }
+                    };
+                    let link_id = tables.next_id();
+                    tables.catalog.push(ProjectAreaLink {
+                        id: link_id,
+                        created_at: Some(now()),
+                        project_id: project.id as i64,
+                        area_id,
+                    });
+                }
+            }
+        }
+
+        Ok(Self { tables: RwLock::new(tables) })
+    }
+
+    fn read<T>(&self, f: impl FnOnce(&Tables) -> T) -> T {
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        f(&tables)
+    }
+
+    fn write<T>(&self, f: impl FnOnce(&mut Tables) -> T) -> T {
+        let mut tables = self.tables.write().unwrap_or_else(|e| e.into_inner());
+        f(&mut tables)
+    }
+}
+
+impl Backend for InMemoryBackend {
+    fn fetch_areas(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<ProjectArea>> {
+        let page = self.read(|t| Page::slice(&t.areas, offset, limit));
+        Box::pin(async move { Ok(page) })
+    }
+
+    fn create_area(&self, area: ProjectAreaDto) -> BackendFuture<'_, ProjectArea> {
+        let created = self.write(|t| {
+            let area = ProjectArea {
+                id: t.next_id(),
+                created_at: Some(now()),
+                title: area.title,
+                category: area.category,
+                desc: area.desc,
+                order: area.order,
+                format: area.format,
+            };
+            t.areas.push(area.clone());
+            area
+        });
+        Box::pin(async move { Ok(created) })
+    }
+
+    fn update_area(&self, area: ProjectArea) -> BackendFuture<'_, ProjectArea> {
+        let updated = self.write(|t| {
+            let existing = t.areas.iter_mut().find(|a| a.id == area.id).ok_or_else(|| not_found("areas"))?;
+            existing.title = area.title;
+            existing.category = area.category;
+            existing.desc = area.desc;
+            existing.order = area.order;
+            Ok(existing.clone())
+        });
+        Box::pin(async move { updated })
+    }
+
+    fn delete_area(&self, area_id: i64) -> BackendFuture<'_, ()> {
+        self.write(|t| {
+            t.areas.retain(|a| a.id != area_id);
+            t.catalog.retain(|c| c.area_id != area_id);
+        });
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn upsert_areas(&self, areas: Vec<ProjectArea>) -> BackendFuture<'_, Vec<ProjectArea>> {
+        let written = self.write(|t| {
+            for area in &areas {
+                match t.areas.iter_mut().find(|a| a.id == area.id) {
+                    Some(existing) => {
+                        existing.title = area.title.clone();
+                        existing.category = area.category.clone();
+                        existing.desc = area.desc.clone();
+                        existing.order = area.order;
+                    }
+                    None => t.areas.push(area.clone()),
+                }
+            }
+            t.areas.iter().filter(|a| areas.iter().any(|area| area.id == a.id)).cloned().collect()
+        });
+        Box::pin(async move { Ok(written) })
+    }
+
+    fn delete_areas(&self, area_ids: Vec<i64>) -> BackendFuture<'_, ()> {
+        self.write(|t| {
+            t.areas.retain(|a| !area_ids.contains(&a.id));
+            t.catalog.retain(|c| !area_ids.contains(&c.area_id));
+        });
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn fetch_projects(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<Project>> {
+        let page = self.read(|t| Page::slice(&t.projects, offset, limit));
+        Box::pin(async move { Ok(page) })
+    }
+
+    fn create_project(&self, project: ProjectDto) -> BackendFuture<'_, Project> {
+        let created = self.write(|t| {
+            let id = t.next_id() as i32;
+            t.log_transitions(&Project::default(), &project, id)?;
+            let project = project.into_project(id, Some(now()));
+            t.projects.push(project.clone());
+            Ok(project)
+        });
+        Box::pin(async move { created })
+    }
+
+    fn update_project(&self, project_id: i32, project: ProjectDto) -> BackendFuture<'_, Project> {
+        let updated = self.write(|t| {
+            let before = t.projects.iter().find(|p| p.id == project_id).cloned().ok_or_else(|| not_found("projects"))?;
+            t.log_transitions(&before, &project, project_id)?;
+            let updated = project.into_project(project_id, before.created_at);
+            if let Some(existing) = t.projects.iter_mut().find(|p| p.id == project_id) {
+                *existing = updated.clone();
+            }
+            Ok(updated)
+        });
+        Box::pin(async move { updated })
+    }
+
+    fn delete_project(&self, project_id: i32) -> BackendFuture<'_, ()> {
+        self.write(|t| {
+            t.projects.retain(|p| p.id != project_id);
+            t.catalog.retain(|c| c.project_id != project_id as i64);
+            t.content.retain(|c| c.project_id != project_id as i64);
+            t.transitions.retain(|c| c.project_id != project_id as i64);
+        });
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn upsert_projects(&self, projects: Vec<Project>) -> BackendFuture<'_, Vec<Project>> {
+        let written = self.write(|t| {
+            for project in &projects {
+                match t.projects.iter_mut().find(|p| p.id == project.id) {
+                    Some(existing) => {
+                        existing.title = project.title.clone();
+                        existing.desc = project.desc.clone();
+                        existing.order = project.order;
+                    }
+                    None => t.projects.push(project.clone()),
+                }
+            }
+            t.projects.iter().filter(|p| projects.iter().any(|project| project.id == p.id)).cloned().collect()
+        });
+        Box::pin(async move { Ok(written) })
+    }
+
+    fn delete_projects(&self, project_ids: Vec<i32>) -> BackendFuture<'_, ()> {
+        self.write(|t| {
+            t.projects.retain(|p| !project_ids.contains(&p.id));
+            t.catalog.retain(|c| !project_ids.contains(&(c.project_id as i32)));
+            t.content.retain(|c| !project_ids.contains(&(c.project_id as i32)));
+            t.transitions.retain(|c| !project_ids.contains(&(c.project_id as i32)));
+        });
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn fetch_transitions(&self, project_id: i64) -> BackendFuture<'_, Vec<ProjectTransition>> {
+        let transitions = self.read(|t| t.transitions.iter().filter(|c| c.project_id == project_id).cloned().collect());
+        Box::pin(async move { Ok(transitions) })
+    }
+
+    fn fetch_catalog(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<ProjectAreaLink>> {
+        let page = self.read(|t| Page::slice(&t.catalog, offset, limit));
+        Box::pin(async move { Ok(page) })
+    }
+
+    fn set_project_areas(&self, project_id: i64, area_ids: Vec<i64>) -> BackendFuture<'_, Vec<ProjectAreaLink>> {
+        let links = self.write(|t| {
+            t.catalog.retain(|c| c.project_id != project_id || area_ids.contains(&c.area_id));
+            for area_id in area_ids {
+                if !t.catalog.iter().any(|c| c.project_id == project_id && c.area_id == area_id) {
+                    let id = t.next_id();
+                    t.catalog.push(ProjectAreaLink {
+                        id,
+                        created_at: Some(now()),
+                        project_id,
+                        area_id,
+                    });
+                }
+            }
+            t.catalog.iter().filter(|c| c.project_id == project_id).cloned().collect()
+        });
+        Box::pin(async move { Ok(links) })
+    }
+
+    fn fetch_content(&self, project_id: i64) -> BackendFuture<'_, Option<ProjectContent>> {
+        let content = self.read(|t| t.content.iter().find(|c| c.project_id == project_id).cloned());
+        Box::pin(async move { Ok(content) })
+    }
+
+    fn create_content(&self, content: ProjectContentDto) -> BackendFuture<'_, ProjectContent> {
+        let created = self.write(|t| {
+            let content = ProjectContent {
+                id: t.next_id(),
+                created_at: Some(now()),
+                text: content.text,
+                project_id: content.project_id,
+            };
+            t.content.push(content.clone());
+            content
+        });
+        Box::pin(async move { Ok(created) })
+    }
+
+    fn update_content(&self, content: ProjectContent) -> BackendFuture<'_, ProjectContent> {
+        let updated = self.write(|t| {
+            let existing = t.content.iter_mut().find(|c| c.id == content.id).ok_or_else(|| not_found("content"))?;
+            existing.text = content.text;
+            Ok(existing.clone())
+        });
+        Box::pin(async move { updated })
+    }
+
+    fn delete_content(&self, content_id: i64) -> BackendFuture<'_, ()> {
+        self.write(|t| t.content.retain(|c| c.id != content_id));
+        Box::pin(async move { Ok(()) })
+    }
+
+    /// Images stay in the page as object URLs, valid until it is reloaded.
+    fn upload_image(&self, _project_id: i64, file: File) -> BackendFuture<'_, String> {
+        let url = Url::create_object_url_with_blob(&file)
+            .map_err(|_| SupabaseError::Request("Could not read the image".to_string()));
+        Box::pin(async move { url })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::PAGE_SIZE;
+    use futures::executor::block_on;
+
+    #[test]
+    fn seeds_projects_and_areas_from_scheme() {
+        let backend = InMemoryBackend::seeded();
+        let projects = block_on(backend.fetch_projects(0, PAGE_SIZE)).unwrap().items;
+        let areas = block_on(backend.fetch_areas(0, PAGE_SIZE)).unwrap().items;
+        let catalog = block_on(backend.fetch_catalog(0, PAGE_SIZE)).unwrap().items;
+
+        assert!(!projects.is_empty());
+        assert!(areas.iter().any(|a| a.category == "Technologies" && a.title == "Computer vision"));
+        // Ids of seeded areas never collide with seeded project ids
+        assert!(areas.iter().all(|a| projects.iter().all(|p| p.id as i64 != a.id)));
+        let welding = projects.iter().find(|p| p.title == "Welding").unwrap();
+        assert!(catalog.iter().any(|c| c.project_id == welding.id as i64));
+        assert_eq!(welding.status, Some(ProjectStatus::InProgress));
+        assert_eq!(welding.maturity, Some(Maturity::Research));
+        assert_eq!(welding.date_started.as_deref(), Some("2024-03-15"));
+        assert!(areas.iter().any(|a| a.category == "Maturity" && a.title == "POC"));
+    }
+
+    #[test]
+    fn project_crud_round_trip() {
+        let backend = InMemoryBackend::default();
+        let created = block_on(backend.create_project(ProjectDto {
+            title: "Blade scan".to_string(),
+            desc: None,
+            order: Some(1),
+            ..Default::default()
+        })).unwrap();
+
+        let updated = block_on(backend.update_project(created.id, ProjectDto {
+            title: "Blade inspection".to_string(),
+            ..created.to_dto()
+        })).unwrap();
+        assert_eq!(updated.title, "Blade inspection");
+
+        block_on(backend.delete_project(created.id)).unwrap();
+        assert_eq!(block_on(backend.fetch_projects(0, PAGE_SIZE)).unwrap().total, Some(0));
+    }
+
+    #[test]
+    fn lifecycle_changes_are_logged_and_checked() {
+        let backend = InMemoryBackend::default();
+        let created = block_on(backend.create_project(ProjectDto {
+            title: "Blade scan".to_string(),
+            status: Some(ProjectStatus::Planning),
+            ..Default::default()
+        })).unwrap();
+        block_on(backend.update_project(created.id, ProjectDto {
+            status: Some(ProjectStatus::InProgress),
+            maturity: Some(Maturity::Research),
+            ..created.to_dto()
+        })).unwrap();
+
+        let err = block_on(backend.update_project(created.id, ProjectDto {
+            status: Some(ProjectStatus::Planning),
+            ..created.to_dto()
+        })).unwrap_err();
+        assert!(matches!(err, SupabaseError::Request(_)));
+
+        let transitions = block_on(backend.fetch_transitions(created.id as i64)).unwrap();
+        let described: Vec<String> = transitions.iter().map(|t| t.describe()).collect();
+        assert_eq!(described, vec!["Planning", "Planning → In progress", "Research"]);
+
+        block_on(backend.delete_project(created.id)).unwrap();
+        assert!(block_on(backend.fetch_transitions(created.id as i64)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn updating_missing_row_is_not_found() {
+        let backend = InMemoryBackend::default();
+        let err = block_on(backend.update_content(ProjectContent {
+            id: 42,
+            created_at: None,
+            text: None,
+            project_id: 1,
+        })).unwrap_err();
+        assert_eq!(err.status(), Some(404));
+    }
+
+    #[test]
+    fn deleting_area_removes_catalog_links() {
+        let backend = InMemoryBackend::default();
+        let area = block_on(backend.create_area(ProjectAreaDto::from_category("Scale".to_string()))).unwrap();
+        block_on(backend.set_project_areas(1, vec![area.id])).unwrap();
+
+        block_on(backend.delete_area(area.id)).unwrap();
+        assert!(block_on(backend.fetch_catalog(0, PAGE_SIZE)).unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn set_project_areas_applies_the_diff_and_keeps_existing_links() {
+        let backend = InMemoryBackend::default();
+        let kept = block_on(backend.set_project_areas(1, vec![10, 11])).unwrap().remove(0);
+        block_on(backend.set_project_areas(2, vec![11])).unwrap();
+
+        let links = block_on(backend.set_project_areas(1, vec![10, 12])).unwrap();
+        let areas: Vec<i64> = links.iter().map(|c| c.area_id).collect();
+        assert_eq!(areas, vec![10, 12]);
+        assert_eq!(links[0].id, kept.id);
+        assert_eq!(block_on(backend.fetch_catalog(0, PAGE_SIZE)).unwrap().total, Some(3));
+    }
+
+    #[test]
+    fn bulk_writes_update_existing_rows_and_cascade_deletes() {
+        let backend = InMemoryBackend::seeded();
+        let mut projects = block_on(backend.fetch_projects(0, PAGE_SIZE)).unwrap().items;
+        projects.reverse();
+        for (i, project) in projects.iter_mut().enumerate() {
+            project.order = Some(i as i32 + 1);
+        }
+
+        let written = block_on(backend.upsert_projects(projects.clone())).unwrap();
+        assert_eq!(written.len(), projects.len());
+        let first = projects[0].id;
+        assert!(written.iter().any(|p| p.id == first && p.order == Some(1)));
+
+        block_on(backend.delete_projects(vec![first])).unwrap();
+        let catalog = block_on(backend.fetch_catalog(0, PAGE_SIZE)).unwrap().items;
+        assert!(catalog.iter().all(|c| c.project_id != first as i64));
+        assert_eq!(block_on(backend.fetch_projects(0, PAGE_SIZE)).unwrap().total, Some(projects.len() - 1));
+    }
+
+    #[test]
+    fn pages_through_projects_with_total() {
+        let backend = InMemoryBackend::seeded();
+        let total = block_on(backend.fetch_projects(0, PAGE_SIZE)).unwrap().items.len();
+
+        let page = block_on(backend.fetch_projects(1, 2)).unwrap();
+        assert_eq!(page.total, Some(total));
+        assert_eq!(page.offset, 1);
+        assert!(page.items.len() <= 2);
+        assert!(block_on(backend.fetch_projects(total, 2)).unwrap().items.is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use futures::future::LocalBoxFuture;
+use leptos::prelude::{provide_context, use_context, Children, GetUntracked};
+use leptos::*;
+use web_sys::File;
+
+use crate::{
+    areas::model::{ProjectArea, ProjectAreaDto},
+    auth::auth_context::use_auth,
+    catalog::model::ProjectAreaLink,
+    content::model::{ProjectContent, ProjectContentDto},
+    projects::model::{Project, ProjectDto, ProjectTransition},
+    shared::environment::{use_environment, BackendKind},
+    supabase::{use_supabase, Page, SupabaseError},
+};
+
+pub mod memory;
+pub mod outbox;
+pub mod realtime;
+mod rest;
+
+pub use memory::InMemoryBackend;
+use outbox::{Outbox, OutboxBackend};
+
+pub type BackendFuture<'a, T> = LocalBoxFuture<'a, Result<T, SupabaseError>>;
+
+/// Rows requested per page when walking a list.
+pub const PAGE_SIZE: usize = 100;
+
+/// Groups of rows a mutation can make stale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resource {
+    Areas,
+    Projects,
+    Catalog,
+    Content,
+}
+
+/// Data operations the contexts need, independent of where the data lives.
+///
+/// Implemented over the Supabase REST API by [`crate::supabase::SupabaseClient`]
+/// and fully in memory by [`InMemoryBackend`] for offline development and tests.
+pub trait Backend: Send + Sync {
+    fn fetch_areas(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<ProjectArea>>;
+    fn create_area(&self, area: ProjectAreaDto) -> BackendFuture<'_, ProjectArea>;
+    fn update_area(&self, area: ProjectArea) -> BackendFuture<'_, ProjectArea>;
+    fn delete_area(&self, area_id: i64) -> BackendFuture<'_, ()>;
+    /// Write many areas in one request, e.g. after renumbering or moving them.
+    fn upsert_areas(&self, areas: Vec<ProjectArea>) -> BackendFuture<'_, Vec<ProjectArea>>;
+    fn delete_areas(&self, area_ids: Vec<i64>) -> BackendFuture<'_, ()>;
+
+    fn fetch_projects(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<Project>>;
+    fn create_project(&self, project: ProjectDto) -> BackendFuture<'_, Project>;
+    fn update_project(&self, project_id: i32, project: ProjectDto) -> BackendFuture<'_, Project>;
+    fn delete_project(&self, project_id: i32) -> BackendFuture<'_, ()>;
+    fn upsert_projects(&self, projects: Vec<Project>) -> BackendFuture<'_, Vec<Project>>;
+    fn delete_projects(&self, project_ids: Vec<i32>) -> BackendFuture<'_, ()>;
+    /// Status and maturity changes of a project, oldest first. They are logged by
+    /// the store as projects are written, never directly.
+    fn fetch_transitions(&self, project_id: i64) -> BackendFuture<'_, Vec<ProjectTransition>>;
+
+    fn fetch_catalog(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<ProjectAreaLink>>;
+    /// Link exactly `area_ids` to the project in one transaction and return its links afterwards.
+    fn set_project_areas(&self, project_id: i64, area_ids: Vec<i64>) -> BackendFuture<'_, Vec<ProjectAreaLink>>;
+
+    fn fetch_content(&self, project_id: i64) -> BackendFuture<'_, Option<ProjectContent>>;
+    fn create_content(&self, content: ProjectContentDto) -> BackendFuture<'_, ProjectContent>;
+    fn update_content(&self, content: ProjectContent) -> BackendFuture<'_, ProjectContent>;
+    fn delete_content(&self, content_id: i64) -> BackendFuture<'_, ()>;
+
+    /// Store an image for a project's content and return the URL to embed it with.
+    fn upload_image(&self, project_id: i64, file: File) -> BackendFuture<'_, String>;
+
+    /// Forget cached reads of `resource` after a mutation, so the next fetch sees it.
+    fn invalidate(&self, _resource: Resource) {}
+}
+
+/// Fetch a list page by page from the start, handing each page to `on_page` as it arrives.
+pub async fn for_each_page<'a, T, F>(
+    fetch: F,
+    mut on_page: impl FnMut(Page<T>),
+) -> Result<(), SupabaseError>
+where
+    F: Fn(usize, usize) -> BackendFuture<'a, Page<T>>,
+{
+    let mut offset = 0;
+    loop {
+        let page = fetch(offset, PAGE_SIZE).await?;
+        let has_more = page.has_more();
+        offset = page.next_offset();
+        on_page(page);
+        if !has_more {
+            return Ok(());
+        }
+    }
+}
+
+/// Provides the data backend chosen by the environment profile: Supabase,
+/// or the in-memory backend seeded from `posts-scheme.json`.
+///
+/// Writes go through an [`Outbox`] so they survive losing the connection.
+#[component]
+pub fn BackendProvider(children: Children) -> impl IntoView {
+    let outbox = Arc::new(match use_environment().backend {
+        BackendKind::Memory => Outbox::in_memory(Arc::new(InMemoryBackend::seeded())),
+        BackendKind::Supabase => Outbox::new(
+            Arc::new(use_supabase()),
+            use_auth().user.0.get_untracked().map(|user| user.id),
+        ),
+    });
+    let backend: Arc<dyn Backend> = Arc::new(OutboxBackend::new(outbox.clone()));
+    provide_context(outbox);
+    provide_context(backend);
+    children()
+}
+
+pub fn use_backend() -> Arc<dyn Backend> {
+    use_context::<Arc<dyn Backend>>().expect(
+        "Backend not found. Make sure you are using BackendProvider."
+    )
+}
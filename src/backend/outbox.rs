@@ -0,0 +1,864 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, RwLock,
+};
+
+use std::time::Duration;
+
+use gloo_storage::{LocalStorage, Storage};
+use leptos::prelude::{
+    on_cleanup, set_interval_with_handle, signal, use_context, window_event_listener, Children, Effect,
+    Get, GetUntracked, ReadSignal, Set, Update, With, WriteSignal,
+};
+use leptos::task::spawn_local;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use web_sys::File;
+
+use crate::{
+    areas::{areas_context::use_areas, model::{ProjectArea, ProjectAreaDto}},
+    auth::auth_context::use_auth,
+    backend::{Backend, BackendFuture, Resource},
+    catalog::{catalog_context::use_catalog, model::ProjectAreaLink},
+    content::{content_context::use_project_content, model::{ProjectContent, ProjectContentDto}},
+    projects::{model::{Project, ProjectDto, ProjectTransition}, projects_context::use_project},
+    shared::task::{Loading, LoadingGuard},
+    supabase::{Page, SupabaseError},
+};
+
+/// Prefix of each user's queue in local storage, `spiral_outbox:<user id>`. A queue is
+/// only ever sent with its owner's session.
+const OUTBOX_STORAGE_KEY: &str = "spiral_outbox";
+/// How often queued writes are retried while the browser claims to be online.
+const REPLAY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A write the contexts asked for. Rows created while offline get a negative
+/// temporary id, replaced by the real one once the create reaches the server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Mutation {
+    CreateArea { temp_id: i64, area: ProjectAreaDto },
+    UpdateArea { area: ProjectArea },
+    DeleteArea { area_id: i64 },
+    UpsertAreas { areas: Vec<ProjectArea> },
+    DeleteAreas { area_ids: Vec<i64> },
+    CreateProject { temp_id: i64, project: ProjectDto },
+    UpdateProject { project_id: i32, project: ProjectDto },
+    DeleteProject { project_id: i32 },
+    UpsertProjects { projects: Vec<Project> },
+    DeleteProjects { project_ids: Vec<i32> },
+    SetProjectAreas { project_id: i64, area_ids: Vec<i64> },
+    CreateContent { temp_id: i64, content: ProjectContentDto },
+    UpdateContent { content: ProjectContent },
+    DeleteContent { content_id: i64 },
+}
+
+impl Mutation {
+    /// Short description for the editor's sync status.
+    pub fn describe(&self) -> String {
+        match self {
+            Mutation::CreateArea { area, .. } => format!("Create area \"{}\"", area.title),
+            Mutation::UpdateArea { area } => format!("Update area \"{}\"", area.title),
+            Mutation::DeleteArea { area_id } => format!("Delete area #{}", area_id),
+            Mutation::UpsertAreas { areas } => format!("Save {} area(s)", areas.len()),
+            Mutation::DeleteAreas { area_ids } => format!("Delete {} area(s)", area_ids.len()),
+            Mutation::CreateProject { project, .. } => format!("Create project \"{}\"", project.title),
+            Mutation::UpdateProject { project, .. } => format!("Update project \"{}\"", project.title),
+            Mutation::DeleteProject { project_id } => format!("Delete project #{}", project_id),
+            Mutation::UpsertProjects { projects } => format!("Save {} project(s)", projects.len()),
+            Mutation::DeleteProjects { project_ids } => format!("Delete {} project(s)", project_ids.len()),
+            Mutation::SetProjectAreas { project_id, area_ids } => {
+                format!("Set {} area(s) of project #{}", area_ids.len(), project_id)
+            }
+            Mutation::CreateContent { content, .. } => format!("Create content of project #{}", content.project_id),
+            Mutation::UpdateContent { content } => format!("Update content of project #{}", content.project_id),
+            Mutation::DeleteContent { content_id } => format!("Delete content #{}", content_id),
+        }
+    }
+
+    /// Point references to a row created offline at the id the server gave it.
+    fn remap(&mut self, resource: Resource, temp_id: i64, real_id: i64) {
+        let swap = |id: &mut i64| {
+            if *id == temp_id {
+                *id = real_id;
+            }
+        };
+        let swap_project = |id: &mut i32| {
+            if *id as i64 == temp_id {
+                *id = real_id as i32;
+            }
+        };
+        match (self, resource) {
+            (Mutation::UpdateArea { area }, Resource::Areas) => swap(&mut area.id),
+            (Mutation::DeleteArea { area_id }, Resource::Areas) => swap(area_id),
+            (Mutation::UpsertAreas { areas }, Resource::Areas) => areas.iter_mut().for_each(|area| swap(&mut area.id)),
+            (Mutation::DeleteAreas { area_ids }, Resource::Areas) => area_ids.iter_mut().for_each(swap),
+            (Mutation::UpdateProject { project_id, .. }, Resource::Projects) => swap_project(project_id),
+            (Mutation::UpsertProjects { projects }, Resource::Projects) => {
+                projects.iter_mut().for_each(|project| swap_project(&mut project.id))
+            }
+            (Mutation::DeleteProjects { project_ids }, Resource::Projects) => project_ids.iter_mut().for_each(swap_project),
+            (Mutation::DeleteProject { project_id }, Resource::Projects) => swap_project(project_id),
+            (Mutation::SetProjectAreas { project_id, .. }, Resource::Projects) => swap(project_id),
+            (Mutation::SetProjectAreas { area_ids, .. }, Resource::Areas) => area_ids.iter_mut().for_each(swap),
+            (Mutation::CreateContent { content, .. }, Resource::Projects) => swap(&mut content.project_id),
+            (Mutation::UpdateContent { content }, Resource::Projects) => swap(&mut content.project_id),
+            (Mutation::UpdateContent { content }, Resource::Content) => swap(&mut content.id),
+            (Mutation::DeleteContent { content_id }, Resource::Content) => swap(content_id),
+            _ => {}
+        }
+    }
+
+    /// Send the mutation. Creates return the temporary and the real id of the new row.
+    async fn send(&self, backend: &dyn Backend) -> Result<Option<(Resource, i64, i64)>, SupabaseError> {
+        match self {
+            Mutation::CreateArea { temp_id, area } => backend
+                .create_area(area.clone())
+                .await
+                .map(|created| Some((Resource::Areas, *temp_id, created.id))),
+            Mutation::UpdateArea { area } => backend.update_area(area.clone()).await.map(|_| None),
+            Mutation::DeleteArea { area_id } => backend.delete_area(*area_id).await.map(|_| None),
+            Mutation::UpsertAreas { areas } => backend.upsert_areas(areas.clone()).await.map(|_| None),
+            Mutation::DeleteAreas { area_ids } => backend.delete_areas(area_ids.clone()).await.map(|_| None),
+            Mutation::CreateProject { temp_id, project } => backend
+                .create_project(project.clone())
+                .await
+                .map(|created| Some((Resource::Projects, *temp_id, created.id as i64))),
+            Mutation::UpdateProject { project_id, project } => {
+                backend.update_project(*project_id, project.clone()).await.map(|_| None)
+            }
+            Mutation::DeleteProject { project_id } => backend.delete_project(*project_id).await.map(|_| None),
+            Mutation::UpsertProjects { projects } => backend.upsert_projects(projects.clone()).await.map(|_| None),
+            Mutation::DeleteProjects { project_ids } => backend.delete_projects(project_ids.clone()).await.map(|_| None),
+            Mutation::SetProjectAreas { project_id, area_ids } => {
+                backend.set_project_areas(*project_id, area_ids.clone()).await.map(|_| None)
+            }
+            Mutation::CreateContent { temp_id, content } => backend
+                .create_content(content.clone())
+                .await
+                .map(|created| Some((Resource::Content, *temp_id, created.id))),
+            Mutation::UpdateContent { content } => backend.update_content(content.clone()).await.map(|_| None),
+            Mutation::DeleteContent { content_id } => backend.delete_content(*content_id).await.map(|_| None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EntryStatus {
+    /// Waiting for the connection, or a refreshed session, to come back.
+    Pending,
+    Sending,
+    /// Rejected by the server when replayed; the queue waits until the user retries or discards it.
+    Conflict(String),
+}
+
+impl EntryStatus {
+    /// Where a write goes when replaying it failed. Only a rejection of the write itself
+    /// is a conflict; an unreachable server or a stale session is waited out.
+    fn after_failed_replay(err: &SupabaseError) -> Self {
+        match err.status() {
+            _ if err.is_offline() => EntryStatus::Pending,
+            // Sent again with the refreshed token
+            Some(401) => EntryStatus::Pending,
+            Some(status) if status >= 500 => EntryStatus::Pending,
+            _ => EntryStatus::Conflict(err.to_string()),
+        }
+    }
+}
+
+/// Ids of the writes queued behind the first conflict. They stay unsent until it is
+/// retried or discarded, although the editor already shows them as made.
+pub fn blocked_by_conflict(entries: &[OutboxEntry]) -> Vec<u64> {
+    entries.iter()
+        .skip_while(|entry| !matches!(entry.status, EntryStatus::Conflict(_)))
+        .skip(1)
+        .map(|entry| entry.id)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: u64,
+    pub mutation: Mutation,
+    pub queued_at: String,
+    pub status: EntryStatus,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredOutbox {
+    entries: Vec<OutboxEntry>,
+    next_id: u64,
+}
+
+/// How the outbox learns whether the browser is online.
+enum Connectivity {
+    Browser,
+    Assumed(AtomicBool),
+}
+
+/// Queue of writes, kept in local storage until the server has accepted them.
+///
+/// Writes are sent straight away when the browser is online and nothing is queued.
+/// Otherwise they wait here, in order, and [`Outbox::replay`] sends them once the
+/// connection is back.
+pub struct Outbox {
+    pub entries: (ReadSignal<Vec<OutboxEntry>>, WriteSignal<Vec<OutboxEntry>>),
+    pub is_replaying: Loading,
+    /// Bumped after queued writes were sent or discarded, so the contexts can reload
+    /// rows that were shown optimistically.
+    pub synced: (ReadSignal<u32>, WriteSignal<u32>),
+    next_id: AtomicU64,
+    /// Id of the user whose writes are queued.
+    owner: RwLock<Option<String>>,
+    connectivity: Connectivity,
+    backend: Arc<dyn Backend>,
+}
+
+impl Outbox {
+    /// Outbox persisted in local storage, restoring what `owner` left queued last time.
+    pub fn new(backend: Arc<dyn Backend>, owner: Option<String>) -> Self {
+        let outbox = Self::with_connectivity(backend, Connectivity::Browser);
+        outbox.set_owner(owner);
+        outbox
+    }
+
+    /// Outbox kept only in memory that assumes it is online, for the in-memory backend and tests.
+    pub fn in_memory(backend: Arc<dyn Backend>) -> Self {
+        Self::with_connectivity(backend, Connectivity::Assumed(AtomicBool::new(true)))
+    }
+
+    fn with_connectivity(backend: Arc<dyn Backend>, connectivity: Connectivity) -> Self {
+        Self {
+            entries: signal(vec![]),
+            is_replaying: Loading::default(),
+            synced: signal(0),
+            next_id: AtomicU64::new(0),
+            owner: RwLock::new(None),
+            connectivity,
+            backend,
+        }
+    }
+
+    fn storage_key(owner: &str) -> String {
+        format!("{}:{}", OUTBOX_STORAGE_KEY, owner)
+    }
+
+    /// Switch to the queue of the signed-in user, so writes are never replayed with
+    /// someone else's session. The previous user's queue stays stored for their next sign-in.
+    pub fn set_owner(&self, owner: Option<String>) {
+        {
+            let mut current = self.owner.write().unwrap_or_else(|e| e.into_inner());
+            if *current == owner {
+                return;
+            }
+            *current = owner.clone();
+        }
+
+        let stored = match (&self.connectivity, &owner) {
+            (Connectivity::Browser, Some(owner)) => {
+                LocalStorage::get::<StoredOutbox>(Self::storage_key(owner)).unwrap_or_default()
+            }
+            _ => StoredOutbox::default(),
+        };
+        // A send interrupted by a reload may or may not have arrived; try it again
+        let entries = stored.entries
+            .into_iter()
+            .map(|entry| match entry.status {
+                EntryStatus::Sending => OutboxEntry { status: EntryStatus::Pending, ..entry },
+                _ => entry,
+            })
+            .collect();
+        self.next_id.store(stored.next_id, Ordering::Relaxed);
+        self.entries.1.set(entries);
+        // Rows the previous user's queue showed optimistically are reloaded away
+        self.synced.1.update(|n| *n += 1);
+    }
+
+    pub fn is_online(&self) -> bool {
+        match &self.connectivity {
+            Connectivity::Browser => web_sys::window().is_some_and(|w| w.navigator().on_line()),
+            Connectivity::Assumed(online) => online.load(Ordering::Relaxed),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn set_assumed_online(&self, value: bool) {
+        if let Connectivity::Assumed(online) = &self.connectivity {
+            online.store(value, Ordering::Relaxed);
+        }
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Id for a row created while its create may still be queued.
+    pub fn temp_id(&self) -> i64 {
+        -(self.next_id() as i64)
+    }
+
+    fn save(&self) {
+        if let Connectivity::Browser = self.connectivity {
+            // Without a signed-in user there is nobody to replay the queue for later
+            let Some(owner) = self.owner.read().unwrap_or_else(|e| e.into_inner()).clone() else { return };
+            let stored = StoredOutbox {
+                entries: self.entries.0.get_untracked(),
+                next_id: self.next_id.load(Ordering::Relaxed),
+            };
+            let _ = LocalStorage::set(Self::storage_key(&owner), stored);
+        }
+    }
+
+    fn enqueue(&self, mutation: Mutation, status: EntryStatus) -> u64 {
+        let id = self.next_id();
+        self.entries.1.update(|entries| entries.push(OutboxEntry {
+            id,
+            mutation,
+            queued_at: chrono::Utc::now().to_rfc3339(),
+            status,
+        }));
+        self.save();
+        id
+    }
+
+    fn set_status(&self, id: u64, status: EntryStatus) {
+        self.entries.1.update(|entries| {
+            if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+                entry.status = status;
+            }
+        });
+        self.save();
+    }
+
+    fn remove(&self, id: u64) {
+        self.entries.1.update(|entries| entries.retain(|e| e.id != id));
+        self.save();
+    }
+
+    fn remap(&self, resource: Resource, temp_id: i64, real_id: i64) {
+        self.entries.1.update(|entries| {
+            for entry in entries.iter_mut() {
+                entry.mutation.remap(resource, temp_id, real_id);
+            }
+        });
+        self.save();
+    }
+
+    /// Run a write through the outbox. `queued` is what the caller sees if the write has to wait.
+    pub async fn run<'a, T>(
+        &self,
+        mutation: Mutation,
+        send: impl FnOnce() -> BackendFuture<'a, T>,
+        queued: T,
+    ) -> Result<T, SupabaseError> {
+        // Later writes may depend on queued ones, so they never overtake them. A write
+        // that is only in flight was made independently of this one and is not waited for.
+        let queued_ahead = self.entries.0.get_untracked()
+            .iter()
+            .any(|entry| entry.status != EntryStatus::Sending);
+        if queued_ahead || !self.is_online() {
+            self.enqueue(mutation, EntryStatus::Pending);
+            return Ok(queued);
+        }
+
+        let id = self.enqueue(mutation, EntryStatus::Sending);
+        match send().await {
+            Err(err) if err.is_offline() => {
+                self.set_status(id, EntryStatus::Pending);
+                Ok(queued)
+            }
+            result => {
+                self.remove(id);
+                result
+            }
+        }
+    }
+
+    /// Send queued writes in order. Stops when the connection drops again or at a conflict.
+    pub async fn replay(&self) {
+        if self.is_replaying.get_untracked() || !self.is_online() {
+            return;
+        }
+        let _replaying = LoadingGuard::new(self.is_replaying);
+
+        let mut sent = false;
+        while let Some(entry) = self.entries.0.get_untracked().first().cloned() {
+            if entry.status != EntryStatus::Pending {
+                break;
+            }
+            self.set_status(entry.id, EntryStatus::Sending);
+            match entry.mutation.send(self.backend.as_ref()).await {
+                Ok(created) => {
+                    self.remove(entry.id);
+                    if let Some((resource, temp_id, real_id)) = created {
+                        self.remap(resource, temp_id, real_id);
+                    }
+                    sent = true;
+                }
+                Err(err) => {
+                    self.set_status(entry.id, EntryStatus::after_failed_replay(&err));
+                    break;
+                }
+            }
+        }
+
+        if sent {
+            self.synced.1.update(|n| *n += 1);
+        }
+    }
+
+    /// Queue a conflicting write to be sent again on the next replay.
+    pub fn retry(&self, id: u64) {
+        self.set_status(id, EntryStatus::Pending);
+    }
+
+    /// Drop a write for good; the contexts reload to undo what it showed.
+    pub fn discard(&self, id: u64) {
+        self.remove(id);
+        self.synced.1.update(|n| *n += 1);
+    }
+}
+
+/// Backend whose writes go through an [`Outbox`]; reads go straight to the wrapped backend.
+pub struct OutboxBackend {
+    outbox: Arc<Outbox>,
+}
+
+impl OutboxBackend {
+    pub fn new(outbox: Arc<Outbox>) -> Self {
+        Self { outbox }
+    }
+
+    fn inner(&self) -> &dyn Backend {
+        self.outbox.backend.as_ref()
+    }
+}
+
+impl Backend for OutboxBackend {
+    fn fetch_areas(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<ProjectArea>> {
+        self.inner().fetch_areas(offset, limit)
+    }
+
+    fn create_area(&self, area: ProjectAreaDto) -> BackendFuture<'_, ProjectArea> {
+        Box::pin(async move {
+            let temp_id = self.outbox.temp_id();
+            let queued = ProjectArea {
+                id: temp_id,
+                created_at: None,
+                title: area.title.clone(),
+                category: area.category.clone(),
+                desc: area.desc.clone(),
+                order: area.order,
+                format: area.format.clone(),
+            };
+            let mutation = Mutation::CreateArea { temp_id, area: area.clone() };
+            self.outbox.run(mutation, || self.inner().create_area(area), queued).await
+        })
+    }
+
+    fn update_area(&self, area: ProjectArea) -> BackendFuture<'_, ProjectArea> {
+        Box::pin(async move {
+            let mutation = Mutation::UpdateArea { area: area.clone() };
+            self.outbox.run(mutation, || self.inner().update_area(area.clone()), area.clone()).await
+        })
+    }
+
+    fn delete_area(&self, area_id: i64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let mutation = Mutation::DeleteArea { area_id };
+            self.outbox.run(mutation, || self.inner().delete_area(area_id), ()).await
+        })
+    }
+
+    fn upsert_areas(&self, areas: Vec<ProjectArea>) -> BackendFuture<'_, Vec<ProjectArea>> {
+        Box::pin(async move {
+            let mutation = Mutation::UpsertAreas { areas: areas.clone() };
+            self.outbox.run(mutation, || self.inner().upsert_areas(areas.clone()), areas.clone()).await
+        })
+    }
+
+    fn delete_areas(&self, area_ids: Vec<i64>) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let mutation = Mutation::DeleteAreas { area_ids: area_ids.clone() };
+            self.outbox.run(mutation, || self.inner().delete_areas(area_ids), ()).await
+        })
+    }
+
+    fn fetch_projects(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<Project>> {
+        self.inner().fetch_projects(offset, limit)
+    }
+
+    fn create_project(&self, project: ProjectDto) -> BackendFuture<'_, Project> {
+        Box::pin(async move {
+            let temp_id = self.outbox.temp_id();
+            let queued = project.clone().into_project(temp_id as i32, None);
+            let mutation = Mutation::CreateProject { temp_id, project: project.clone() };
+            self.outbox.run(mutation, || self.inner().create_project(project), queued).await
+        })
+    }
+
+    fn update_project(&self, project_id: i32, project: ProjectDto) -> BackendFuture<'_, Project> {
+        Box::pin(async move {
+            let queued = project.clone().into_project(project_id, None);
+            let mutation = Mutation::UpdateProject { project_id, project: project.clone() };
+            self.outbox.run(mutation, || self.inner().update_project(project_id, project), queued).await
+        })
+    }
+
+    fn delete_project(&self, project_id: i32) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let mutation = Mutation::DeleteProject { project_id };
+            self.outbox.run(mutation, || self.inner().delete_project(project_id), ()).await
+        })
+    }
+
+    fn upsert_projects(&self, projects: Vec<Project>) -> BackendFuture<'_, Vec<Project>> {
+        Box::pin(async move {
+            let mutation = Mutation::UpsertProjects { projects: projects.clone() };
+            self.outbox.run(mutation, || self.inner().upsert_projects(projects.clone()), projects.clone()).await
+        })
+    }
+
+    fn delete_projects(&self, project_ids: Vec<i32>) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let mutation = Mutation::DeleteProjects { project_ids: project_ids.clone() };
+            self.outbox.run(mutation, || self.inner().delete_projects(project_ids), ()).await
+        })
+    }
+
+    fn fetch_transitions(&self, project_id: i64) -> BackendFuture<'_, Vec<ProjectTransition>> {
+        self.inner().fetch_transitions(project_id)
+    }
+
+    fn fetch_catalog(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<ProjectAreaLink>> {
+        self.inner().fetch_catalog(offset, limit)
+    }
+
+    fn set_project_areas(&self, project_id: i64, area_ids: Vec<i64>) -> BackendFuture<'_, Vec<ProjectAreaLink>> {
+        Box::pin(async move {
+            // Existing links are not known here; the catalog is reloaded once this is sent
+            let queued = area_ids.iter()
+                .map(|&area_id| ProjectAreaLink {
+                    id: self.outbox.temp_id(),
+                    created_at: None,
+                    project_id,
+                    area_id,
+                })
+                .collect();
+            let mutation = Mutation::SetProjectAreas { project_id, area_ids: area_ids.clone() };
+            self.outbox.run(mutation, || self.inner().set_project_areas(project_id, area_ids), queued).await
+        })
+    }
+
+    fn fetch_content(&self, project_id: i64) -> BackendFuture<'_, Option<ProjectContent>> {
+        self.inner().fetch_content(project_id)
+    }
+
+    fn create_content(&self, content: ProjectContentDto) -> BackendFuture<'_, ProjectContent> {
+        Box::pin(async move {
+            let temp_id = self.outbox.temp_id();
+            let queued = ProjectContent {
+                id: temp_id,
+                created_at: None,
+                text: content.text.clone(),
+                project_id: content.project_id,
+            };
+            let mutation = Mutation::CreateContent { temp_id, content: content.clone() };
+            self.outbox.run(mutation, || self.inner().create_content(content), queued).await
+        })
+    }
+
+    fn update_content(&self, content: ProjectContent) -> BackendFuture<'_, ProjectContent> {
+        Box::pin(async move {
+            let mutation = Mutation::UpdateContent { content: content.clone() };
+            self.outbox.run(mutation, || self.inner().update_content(content.clone()), content.clone()).await
+        })
+    }
+
+    fn delete_content(&self, content_id: i64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let mutation = Mutation::DeleteContent { content_id };
+            self.outbox.run(mutation, || self.inner().delete_content(content_id), ()).await
+        })
+    }
+
+    /// Uploads cannot wait in the outbox, so they need a connection.
+    fn upload_image(&self, project_id: i64, file: File) -> BackendFuture<'_, String> {
+        self.inner().upload_image(project_id, file)
+    }
+
+    fn invalidate(&self, resource: Resource) {
+        self.inner().invalidate(resource);
+    }
+}
+
+pub fn use_outbox() -> Arc<Outbox> {
+    use_context::<Arc<Outbox>>().expect(
+        "Outbox not found. Make sure you are using BackendProvider."
+    )
+}
+
+/// Replays the outbox at startup, when the browser comes back online and
+/// periodically while writes are queued, then reloads the contexts.
+#[component]
+pub fn OutboxSync(children: Children) -> impl IntoView {
+    let outbox = use_outbox();
+    let replay = {
+        let outbox = outbox.clone();
+        move || {
+            let outbox = outbox.clone();
+            spawn_local(async move { outbox.replay().await });
+        }
+    };
+
+    replay();
+    // Follow the signed-in user to their own queue, and send what they left queued
+    let user = use_auth().user.0;
+    Effect::new({
+        let outbox = outbox.clone();
+        let replay = replay.clone();
+        move |previous: Option<Option<String>>| {
+            let owner = user.get().map(|user| user.id);
+            if previous.is_some_and(|previous| previous != owner) {
+                outbox.set_owner(owner.clone());
+                replay();
+            }
+            owner
+        }
+    });
+    // Writes held back by an expired session go out once it is refreshed
+    let session = use_auth().session.0;
+    Effect::new({
+        let replay = replay.clone();
+        move |previous: Option<Option<String>>| {
+            let token = session.with(|session| session.as_ref().map(|session| session.access_token.clone()));
+            if previous.is_some_and(|previous| previous != token) {
+                replay();
+            }
+            token
+        }
+    });
+    let online = window_event_listener(ev::online, {
+        let replay = replay.clone();
+        move |_| replay()
+    });
+    let interval = set_interval_with_handle({
+        let outbox = outbox.clone();
+        move || {
+            if !outbox.entries.0.get_untracked().is_empty() {
+                replay();
+            }
+        }
+    }, REPLAY_INTERVAL).ok();
+    on_cleanup(move || {
+        online.remove();
+        if let Some(interval) = interval {
+            interval.clear();
+        }
+    });
+
+    let projects = use_project();
+    let areas = use_areas();
+    let catalog = use_catalog();
+    let content = use_project_content();
+    Effect::new(move |previous: Option<u32>| {
+        let synced = outbox.synced.0.get();
+        if previous.is_some_and(|previous| previous != synced) {
+            let (projects, areas, catalog, content) = (projects.clone(), areas.clone(), catalog.clone(), content.clone());
+            spawn_local(async move {
+                projects.fetch_projects().await;
+                areas.fetch_areas().await;
+                catalog.fetch_catalog().await;
+                content.fetch_project_content().await;
+            });
+        }
+        synced
+    });
+
+    children()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{InMemoryBackend, PAGE_SIZE};
+    use std::task::Poll;
+
+    use futures::executor::block_on;
+    use leptos::prelude::Get;
+
+    fn setup() -> (Arc<InMemoryBackend>, Arc<Outbox>, OutboxBackend) {
+        let server = Arc::new(InMemoryBackend::default());
+        let outbox = Arc::new(Outbox::in_memory(server.clone()));
+        let backend = OutboxBackend::new(outbox.clone());
+        (server, outbox, backend)
+    }
+
+    fn project(title: &str) -> ProjectDto {
+        ProjectDto {
+            title: title.to_string(),
+            desc: None,
+            order: Some(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn online_writes_go_straight_through() {
+        let (server, outbox, backend) = setup();
+        let created = block_on(backend.create_project(project("Welding"))).unwrap();
+
+        assert!(created.id > 0);
+        assert!(outbox.entries.0.get().is_empty());
+        assert_eq!(block_on(server.fetch_projects(0, PAGE_SIZE)).unwrap().items, vec![created]);
+    }
+
+    #[test]
+    fn offline_writes_replay_in_order_with_real_ids() {
+        let (server, outbox, backend) = setup();
+        outbox.set_assumed_online(false);
+
+        let queued = block_on(backend.create_project(project("Welding"))).unwrap();
+        assert!(queued.id < 0);
+        block_on(backend.update_project(queued.id, project("Welding QA"))).unwrap();
+        block_on(backend.set_project_areas(queued.id as i64, vec![7])).unwrap();
+        assert_eq!(outbox.entries.0.get().len(), 3);
+        assert_eq!(block_on(server.fetch_projects(0, PAGE_SIZE)).unwrap().total, Some(0));
+
+        outbox.set_assumed_online(true);
+        block_on(outbox.replay());
+
+        assert!(outbox.entries.0.get().is_empty());
+        assert_eq!(outbox.synced.0.get(), 1);
+        let projects = block_on(server.fetch_projects(0, PAGE_SIZE)).unwrap().items;
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].title, "Welding QA");
+        let catalog = block_on(server.fetch_catalog(0, PAGE_SIZE)).unwrap().items;
+        assert_eq!(catalog[0].project_id, projects[0].id as i64);
+    }
+
+    #[test]
+    fn rejected_replay_is_a_conflict_that_blocks_the_queue() {
+        let (server, outbox, backend) = setup();
+        outbox.set_assumed_online(false);
+        block_on(backend.update_project(42, project("Gone"))).unwrap();
+        block_on(backend.create_project(project("Next"))).unwrap();
+
+        outbox.set_assumed_online(true);
+        block_on(outbox.replay());
+
+        let entries = outbox.entries.0.get();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(entries[0].status, EntryStatus::Conflict(_)));
+        assert_eq!(block_on(server.fetch_projects(0, PAGE_SIZE)).unwrap().total, Some(0));
+
+        outbox.discard(entries[0].id);
+        block_on(outbox.replay());
+        assert!(outbox.entries.0.get().is_empty());
+        assert_eq!(block_on(server.fetch_projects(0, PAGE_SIZE)).unwrap().total, Some(1));
+    }
+
+    #[test]
+    fn writes_behind_a_conflict_are_reported_as_blocked() {
+        let (_, outbox, backend) = setup();
+        outbox.set_assumed_online(false);
+        block_on(backend.create_project(project("Before"))).unwrap();
+        block_on(backend.update_project(42, project("Gone"))).unwrap();
+        block_on(backend.create_project(project("After"))).unwrap();
+        outbox.set_assumed_online(true);
+        block_on(outbox.replay());
+
+        let entries = outbox.entries.0.get();
+        assert!(matches!(entries[0].status, EntryStatus::Conflict(_)));
+        assert_eq!(blocked_by_conflict(&entries), vec![entries[1].id]);
+        outbox.discard(entries[0].id);
+        assert!(blocked_by_conflict(&outbox.entries.0.get()).is_empty());
+    }
+
+    #[test]
+    fn another_user_does_not_inherit_the_queue() {
+        let (server, outbox, backend) = setup();
+        outbox.set_owner(Some("a".to_string()));
+        outbox.set_assumed_online(false);
+        block_on(backend.create_project(project("Welding"))).unwrap();
+
+        outbox.set_owner(Some("b".to_string()));
+        assert!(outbox.entries.0.get().is_empty());
+        outbox.set_assumed_online(true);
+        block_on(outbox.replay());
+        assert_eq!(block_on(server.fetch_projects(0, PAGE_SIZE)).unwrap().total, Some(0));
+    }
+
+    /// Stays pending for one poll, so a send is still in flight when the next write starts.
+    async fn in_flight() {
+        let mut polled = false;
+        futures::future::poll_fn(|cx| {
+            if polled {
+                return Poll::Ready(());
+            }
+            polled = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }).await
+    }
+
+    #[test]
+    fn a_write_in_flight_does_not_queue_the_next_one() {
+        let (server, outbox, _) = setup();
+        let send = |title: &'static str, wait: bool| {
+            let server = server.clone();
+            move || -> BackendFuture<'static, Project> {
+                Box::pin(async move {
+                    if wait {
+                        in_flight().await;
+                    }
+                    server.create_project(project(title)).await
+                })
+            }
+        };
+        let create = |title: &str| Mutation::CreateProject { temp_id: outbox.temp_id(), project: project(title) };
+
+        let (first, second) = block_on(futures::future::join(
+            outbox.run(create("First"), send("First", true), Project::default()),
+            outbox.run(create("Second"), send("Second", false), Project::default()),
+        ));
+
+        assert!(first.unwrap().id > 0);
+        assert!(second.unwrap().id > 0);
+        assert!(outbox.entries.0.get().is_empty());
+        assert_eq!(block_on(server.fetch_projects(0, PAGE_SIZE)).unwrap().total, Some(2));
+    }
+
+    #[test]
+    fn only_rejected_writes_become_conflicts() {
+        let http = |status, text| SupabaseError::from_status(status, "/rest/v1/projects", text);
+        let cases = [
+            (SupabaseError::Timeout, false),
+            (SupabaseError::AuthExpired, false),
+            (http(401, r#"{"code":"PGRST301","message":"JWT expired"}"#), false),
+            (http(503, ""), false),
+            (http(409, r#"{"code":"23505","message":"duplicate key value violates unique constraint"}"#), true),
+            (http(412, ""), true),
+            (http(403, r#"{"code":"42501","message":"permission denied"}"#), true),
+        ];
+        for (err, conflict) in cases {
+            let status = EntryStatus::after_failed_replay(&err);
+            assert_eq!(matches!(status, EntryStatus::Conflict(_)), conflict, "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn mutations_survive_serialization() {
+        let mutation = Mutation::UpdateContent {
+            content: ProjectContent {
+                id: 3,
+                created_at: None,
+                text: Some("# Notes".to_string()),
+                project_id: 1,
+            },
+        };
+        let json = serde_json::to_string(&mutation).unwrap();
+        assert!(json.contains("\"op\":\"update_content\""));
+        assert_eq!(serde_json::from_str::<Mutation>(&json).unwrap(), mutation);
+    }
+}
@@ -0,0 +1,61 @@
+use leptos::prelude::{Children, Effect, Get, StoredValue, WithValue};
+use leptos::*;
+
+use crate::{
+    areas::areas_context::use_areas,
+    auth::auth_context::use_auth,
+    backend::{use_backend, Resource},
+    catalog::catalog_context::use_catalog,
+    content::content_context::use_project_content,
+    projects::projects_context::use_project,
+    shared::environment::{use_environment, BackendKind},
+    supabase::{
+        realtime::{RealtimeProtocol, RealtimeSocket},
+        use_supabase,
+    },
+};
+
+const TABLES: [&str; 4] = ["projects", "areas", "catalog", "content"];
+
+/// Keeps the contexts in step with changes other tabs and editors make, through
+/// Supabase Realtime. Does nothing for the in-memory backend.
+#[component]
+pub fn RealtimeSync(children: Children) -> impl IntoView {
+    if use_environment().backend == BackendKind::Supabase {
+        let client = use_supabase();
+        let auth = use_auth();
+        let backend = use_backend();
+        let projects = use_project();
+        let areas = use_areas();
+        let catalog = use_catalog();
+        let content = use_project_content();
+
+        let protocol = RealtimeProtocol::new(&TABLES, client.access_token());
+        let socket = RealtimeSocket::connect(client.realtime_endpoint(), protocol, move |change| {
+            logging::log!("Realtime {:?} on {}", change.kind, change.table);
+            let resource = match change.table.as_str() {
+                "projects" => Resource::Projects,
+                "areas" => Resource::Areas,
+                "catalog" => Resource::Catalog,
+                "content" => Resource::Content,
+                _ => return,
+            };
+            backend.invalidate(resource);
+            match resource {
+                Resource::Projects => projects.apply_change(&change),
+                Resource::Areas => areas.apply_change(&change),
+                Resource::Catalog => catalog.apply_change(&change),
+                Resource::Content => content.apply_change(&change),
+            }
+        });
+        // Closed when the owner is disposed
+        let socket = StoredValue::new_local(socket);
+
+        Effect::new(move || {
+            let token = auth.session.0.get().map(|session| session.access_token);
+            socket.with_value(|socket| socket.set_access_token(token));
+        });
+    }
+
+    children()
+}
@@ -0,0 +1,237 @@
+use js_sys::Date;
+use serde::Serialize;
+use web_sys::File;
+
+use crate::{
+    areas::model::{ProjectArea, ProjectAreaDto},
+    backend::{Backend, BackendFuture, Resource},
+    catalog::model::ProjectAreaLink,
+    content::model::{ProjectContent, ProjectContentDto},
+    projects::model::{Project, ProjectDto, ProjectTransition},
+    supabase::{project_image_path, Page, Query, SupabaseClient},
+};
+
+/// Arguments of the `set_project_areas` database function.
+#[derive(Serialize)]
+struct SetProjectAreasArgs {
+    p_project_id: i64,
+    p_area_ids: Vec<i64>,
+}
+
+/// Public Storage bucket holding images embedded in project content.
+const IMAGES_BUCKET: &str = "project-images";
+
+/// Images are not referenced by any row, so they are cleaned up with their project.
+/// Failures are ignored: a leftover image is harmless.
+async fn remove_project_images(client: &SupabaseClient, project_id: i32) {
+    let images = client.storage(IMAGES_BUCKET);
+    let folder = format!("projects/{}", project_id);
+    if let Ok(objects) = images.list(&folder).await {
+        let paths: Vec<String> = objects.into_iter()
+            .filter(|object| object.id.is_some())
+            .map(|object| format!("{}/{}", folder, object.name))
+            .collect();
+        let _ = images.remove(&paths).await;
+    }
+}
+
+impl Backend for SupabaseClient {
+    fn fetch_areas(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<ProjectArea>> {
+        Box::pin(async move {
+            let query = Query::table("areas_view")
+                .select("*")
+                .order_asc("id")
+                .range(offset, offset + limit.max(1) - 1);
+            self.get_page(&query).await
+        })
+    }
+
+    fn create_area(&self, area: ProjectAreaDto) -> BackendFuture<'_, ProjectArea> {
+        Box::pin(async move {
+            let new_area = serde_json::json!({
+                "title": area.title,
+                "category": area.category,
+                "desc": area.desc
+            });
+            self.insert("areas", &new_area).await
+        })
+    }
+
+    fn update_area(&self, area: ProjectArea) -> BackendFuture<'_, ProjectArea> {
+        Box::pin(async move {
+            let updated_area = serde_json::json!({
+                "title": area.title,
+                "category": area.category,
+                "desc": area.desc,
+                "order": area.order
+            });
+            let query = Query::table("areas").eq("id", area.id);
+            self.update(&query.to_string(), &updated_area).await
+        })
+    }
+
+    fn delete_area(&self, area_id: i64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let query = Query::table("areas").eq("id", area_id);
+            self.delete(&query.to_string()).await
+        })
+    }
+
+    fn upsert_areas(&self, areas: Vec<ProjectArea>) -> BackendFuture<'_, Vec<ProjectArea>> {
+        Box::pin(async move {
+            let rows: Vec<_> = areas.iter().map(|area| serde_json::json!({
+                "id": area.id,
+                "title": area.title,
+                "category": area.category,
+                "desc": area.desc,
+                "order": area.order
+            })).collect();
+            self.upsert("areas", &rows).await
+        })
+    }
+
+    fn delete_areas(&self, area_ids: Vec<i64>) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            if area_ids.is_empty() {
+                return Ok(());
+            }
+            self.delete_where(&Query::table("areas").in_("id", area_ids)).await
+        })
+    }
+
+    fn fetch_projects(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<Project>> {
+        Box::pin(async move {
+            let query = Query::table("projects")
+                .select("*")
+                .order_asc("id")
+                .range(offset, offset + limit.max(1) - 1);
+            self.get_page(&query).await
+        })
+    }
+
+    fn create_project(&self, project: ProjectDto) -> BackendFuture<'_, Project> {
+        Box::pin(async move { self.insert("projects", &project).await })
+    }
+
+    fn update_project(&self, project_id: i32, project: ProjectDto) -> BackendFuture<'_, Project> {
+        Box::pin(async move {
+            let query = Query::table("projects").eq("id", project_id);
+            self.update(&query.to_string(), &project).await
+        })
+    }
+
+    fn delete_project(&self, project_id: i32) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let query = Query::table("projects").eq("id", project_id);
+            self.delete(&query.to_string()).await?;
+            remove_project_images(self, project_id).await;
+            Ok(())
+        })
+    }
+
+    fn upsert_projects(&self, projects: Vec<Project>) -> BackendFuture<'_, Vec<Project>> {
+        Box::pin(async move {
+            let rows: Vec<_> = projects.iter().map(|project| serde_json::json!({
+                "id": project.id,
+                "title": project.title,
+                "desc": project.desc,
+                "order": project.order
+            })).collect();
+            self.upsert("projects", &rows).await
+        })
+    }
+
+    fn delete_projects(&self, project_ids: Vec<i32>) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            if project_ids.is_empty() {
+                return Ok(());
+            }
+            self.delete_where(&Query::table("projects").in_("id", project_ids.iter())).await?;
+            for project_id in project_ids {
+                remove_project_images(self, project_id).await;
+            }
+            Ok(())
+        })
+    }
+
+    fn fetch_transitions(&self, project_id: i64) -> BackendFuture<'_, Vec<ProjectTransition>> {
+        Box::pin(async move {
+            let query = Query::table("project_transitions")
+                .select("*")
+                .eq("project_id", project_id)
+                .order_asc("created_at");
+            self.get(&query.to_string()).await
+        })
+    }
+
+    fn fetch_catalog(&self, offset: usize, limit: usize) -> BackendFuture<'_, Page<ProjectAreaLink>> {
+        Box::pin(async move {
+            let query = Query::table("catalog")
+                .select("*")
+                .order_asc("id")
+                .range(offset, offset + limit.max(1) - 1);
+            self.get_page(&query).await
+        })
+    }
+
+    fn set_project_areas(&self, project_id: i64, area_ids: Vec<i64>) -> BackendFuture<'_, Vec<ProjectAreaLink>> {
+        Box::pin(async move {
+            let args = SetProjectAreasArgs {
+                p_project_id: project_id,
+                p_area_ids: area_ids,
+            };
+            self.rpc("set_project_areas", &args).await
+        })
+    }
+
+    fn fetch_content(&self, project_id: i64) -> BackendFuture<'_, Option<ProjectContent>> {
+        Box::pin(async move {
+            let query = Query::table("content").eq("project_id", project_id).select("*");
+            let items: Vec<ProjectContent> = self.get(&query.to_string()).await?;
+            // Get the first (and should be only) project content for this project
+            Ok(items.into_iter().next())
+        })
+    }
+
+    fn create_content(&self, content: ProjectContentDto) -> BackendFuture<'_, ProjectContent> {
+        Box::pin(async move { self.insert("content", &content).await })
+    }
+
+    fn update_content(&self, content: ProjectContent) -> BackendFuture<'_, ProjectContent> {
+        Box::pin(async move {
+            let updated_content = serde_json::json!({
+                "text": content.text,
+            });
+            let query = Query::table("content").eq("id", content.id);
+            self.update(&query.to_string(), &updated_content).await
+        })
+    }
+
+    fn delete_content(&self, content_id: i64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let query = Query::table("content").eq("id", content_id);
+            self.delete(&query.to_string()).await
+        })
+    }
+
+    fn upload_image(&self, project_id: i64, file: File) -> BackendFuture<'_, String> {
+        Box::pin(async move {
+            let images = self.storage(IMAGES_BUCKET);
+            let path = project_image_path(project_id, &file.name(), Date::now() as u64);
+            let path = images.upload(&path, &file, &file.type_()).await?;
+            Ok(images.public_url(&path))
+        })
+    }
+
+    fn invalidate(&self, resource: Resource) {
+        let tables: &[&str] = match resource {
+            Resource::Areas => &["areas", "areas_view"],
+            Resource::Projects => &["projects", "project_transitions"],
+            Resource::Catalog => &["catalog"],
+            Resource::Content => &["content"],
+        };
+        for table in tables {
+            self.invalidate_table(table);
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::catalog::model::{ProjectAreaLink};
-use crate::supabase::{supabase_get, supabase_post, supabase_delete};
+use crate::backend::{for_each_page, use_backend, Backend, Resource};
+use crate::shared::task::{spawn_cancellable, Loading, LoadingGuard};
+use crate::supabase::{realtime::Change, SupabaseError};
 use leptos::{
     logging,
     prelude::{
@@ -12,7 +14,6 @@ use leptos::{
         Update,
         WriteSignal,
     },
-    task::spawn_local,
     *,
 };
 use std::sync::Arc;
@@ -23,86 +24,72 @@ use leptos::prelude::Get;
 #[derive(Clone)]
 pub struct CatalogContext {
     pub catalog: (ReadSignal<Vec<ProjectAreaLink>>, WriteSignal<Vec<ProjectAreaLink>>),
-    pub is_loading: (ReadSignal<bool>, WriteSignal<bool>),
-    pub error: (ReadSignal<Option<String>>, WriteSignal<Option<String>>),
-    url_path: String,
+    pub is_loading: Loading,
+    pub error: (ReadSignal<Option<SupabaseError>>, WriteSignal<Option<SupabaseError>>),
+    backend: Arc<dyn Backend>,
 }
 
 impl CatalogContext {
-    pub fn new() -> Self {
+    pub fn new(backend: Arc<dyn Backend>) -> Self {
         Self {
             catalog: signal::<Vec<ProjectAreaLink>>(vec![]),
-            is_loading: signal(false),
+            is_loading: Loading::default(),
             error: signal(None),
-            url_path: "/rest/v1/catalog?select=*".to_string(),
+            backend,
         }
     }
 
     pub async fn fetch_catalog(&self) {
-        self.is_loading.1.try_update(|v| {
-            *v = true;
-        });
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| {
             *e = None;
         });
-        match supabase_get::<Vec<ProjectAreaLink>>(&self.url_path).await {
-            Ok(items) => {
-                logging::log!("Fetched catalog successfully: {:?}", items);
-                self.catalog.1.set(items);
+        let mut loaded = vec![];
+        let result = for_each_page(
+            |offset, limit| self.backend.fetch_catalog(offset, limit),
+            |page| {
+                self.catalog.1.update(|items| page.merge_into(items, |c| c.id));
+                loaded.extend(page.items);
+            },
+        ).await;
+        match result {
+            Ok(_) => {
+                logging::log!("Fetched {} catalog links successfully", loaded.len());
+                self.catalog.1.set(loaded);
             }
             Err(err) => {
                 logging::log!("Error fetching catalog: {}", err);
+                self.error.1.set(Some(err));
             }
         }
     }
 
-    pub async fn add_project_area_relation(&self, project_id: i64, area_id: i64) -> Result<(), String> {
-        self.is_loading.1.try_update(|v| *v = true);
-        
-        let relation_data = serde_json::json!({
-            "project_id": project_id,
-            "area_id": area_id
-        });
-        
-        match supabase_post::<ProjectAreaLink, serde_json::Value>("/rest/v1/catalog", &relation_data).await {
-            Ok(relation) => {
-                self.catalog.1.update(|catalog| {
-                    catalog.push(relation);
-                });
-                self.is_loading.1.try_update(|v| *v = false);
-                Ok(())
-            }
-            Err(err) => {
-                logging::log!("Error adding project-area relation: {}", err);
-                self.is_loading.1.try_update(|v| *v = false);
-                Err(err)
-            }
-        }
-    }
-
-    pub async fn remove_project_relations(&self, project_id: i64, area_id: i64) -> Result<(), String> {
-        self.is_loading.1.try_update(|v| *v = true);
+    /// Link exactly `area_ids` to the project. The whole diff is applied in one
+    /// transaction, then the project's links are replaced with what the server returned.
+    pub async fn set_project_areas(&self, project_id: i64, area_ids: Vec<i64>) -> Result<(), SupabaseError> {
+        let _loading = LoadingGuard::new(self.is_loading);
+        self.error.1.set(None);
 
-        let url = format!("/rest/v1/catalog?project_id=eq.{}&area_id=eq.{}", project_id, area_id);
-        match supabase_delete(&url).await {
-            Ok(_) => {
-                // Remove from local state
-                self.catalog.1.update(|catalog| {
-                    catalog.retain(|c| c.project_id != project_id || c.area_id != area_id);
-                });
-                self.is_loading.1.try_update(|v| *v = false);
+        match self.backend.set_project_areas(project_id, area_ids).await {
+            Ok(links) => {
+                self.backend.invalidate(Resource::Catalog);
+                self.catalog.1.update(|catalog| replace_project_links(catalog, project_id, links));
                 Ok(())
             }
             Err(err) => {
-                logging::log!("Error removing project relations: {}", err);
-                self.is_loading.1.try_update(|v| *v = false);
+                logging::log!("Error setting project areas: {}", err);
+                self.error.1.set(Some(err.clone()));
                 Err(err)
             }
         }
     }
 
-
-    
+    /// Apply a change made elsewhere, received over Realtime.
+    pub fn apply_change(&self, change: &Change) {
+        self.catalog.1.update(|catalog| {
+            change.apply(catalog, |c| c.id);
+        });
+    }
 
     pub fn get_project_areas_ids(&self, project_id: i64) -> Vec<i64> {
         let current_catalog = self.catalog.0.get();
@@ -116,9 +103,14 @@ impl CatalogContext {
 
 }
 
+fn replace_project_links(catalog: &mut Vec<ProjectAreaLink>, project_id: i64, links: Vec<ProjectAreaLink>) {
+    catalog.retain(|c| c.project_id != project_id);
+    catalog.extend(links);
+}
+
 #[component]
 pub fn CatalogContextProvider(children: Children) -> impl IntoView {
-    let editor_context = Arc::new(CatalogContext::new());
+    let editor_context = Arc::new(CatalogContext::new(use_backend()));
    
     provide_context(editor_context);
 
@@ -128,7 +120,7 @@ pub fn CatalogContextProvider(children: Children) -> impl IntoView {
 #[component]
 pub fn CatalogRoute(children: Children) -> impl IntoView {
     let catalog_context = use_catalog();
-    spawn_local(async move {
+    spawn_cancellable(async move {
         catalog_context.fetch_catalog().await;
     });
 
@@ -141,3 +133,19 @@ pub fn use_catalog() -> Arc<CatalogContext> {
         "CatalogContext not found. Make sure you are using CatalogContextProvider."
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(id: i64, project_id: i64, area_id: i64) -> ProjectAreaLink {
+        ProjectAreaLink { id, created_at: None, project_id, area_id }
+    }
+
+    #[test]
+    fn replacing_links_leaves_other_projects_alone() {
+        let mut catalog = vec![link(1, 1, 10), link(2, 2, 10), link(3, 1, 11)];
+        replace_project_links(&mut catalog, 1, vec![link(1, 1, 10), link(4, 1, 12)]);
+        assert_eq!(catalog, vec![link(2, 2, 10), link(1, 1, 10), link(4, 1, 12)]);
+    }
+}
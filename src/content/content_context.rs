@@ -1,33 +1,34 @@
-use crate::{content::model::ProjectContent, projects::projects_context::ProjectURLParams, supabase::{supabase_delete, supabase_get, supabase_patch, supabase_post}};
+use crate::{backend::{use_backend, Backend, Resource}, content::model::{ProjectContent, ProjectContentDto}, projects::projects_context::ProjectURLParams, shared::task::{spawn_cancellable, Loading, LoadingGuard}, supabase::{realtime::{Change, ChangeKind}, SupabaseError}};
 use leptos::{
     logging,
     prelude::{
         provide_context, signal, use_context, Children, Effect, Read, ReadSignal, Set, Update, WriteSignal
     },
-    task::spawn_local,
     *,
 };
 use leptos_router::hooks::use_params;
 use std::sync::Arc;
-use leptos::prelude::Get;
+use leptos::prelude::{Get, GetUntracked};
 
 
 
 #[derive(Clone)]
 pub struct ProjectContentContext {
     pub project_content: (ReadSignal<Option<ProjectContent>>, WriteSignal<Option<ProjectContent>>),
-    pub is_loading: (ReadSignal<bool>, WriteSignal<bool>),
-    pub error: (ReadSignal<Option<String>>, WriteSignal<Option<String>>),
+    pub is_loading: Loading,
+    pub error: (ReadSignal<Option<SupabaseError>>, WriteSignal<Option<SupabaseError>>),
     pub project_id: (ReadSignal<Option<i64>>, WriteSignal<Option<i64>>),
+    backend: Arc<dyn Backend>,
 }
 
 impl ProjectContentContext {
-    pub fn new() -> Self {
+    pub fn new(backend: Arc<dyn Backend>) -> Self {
         Self {
             project_content: signal::<Option<ProjectContent>>(None),
-            is_loading: signal(false),
+            is_loading: Loading::default(),
             error: signal(None),
             project_id: signal(None),
+            backend,
         }
     }
 
@@ -40,24 +41,19 @@ impl ProjectContentContext {
             Some(id) => id,
             None => {
                 logging::log!("No project ID set");
-                self.error.1.set(Some("No project ID set".to_string()));
+                self.error.1.set(Some(SupabaseError::Request("No project ID set".to_string())));
                 return;
             }
         };
 
-        self.is_loading.1.try_update(|v| {
-            *v = true;
-        });
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| {
             *e = None;
         });
         
-        let url_path = format!("/rest/v1/content?project_id=eq.{}&select=*", project_id);
-        match supabase_get::<Vec<ProjectContent>>(&url_path).await {
-            Ok(items) => {
-                logging::log!("Fetched project content successfully: {:?}", items);
-                // Get the first (and should be only) project content for this project
-                let content = items.into_iter().next();
+        match self.backend.fetch_content(project_id).await {
+            Ok(content) => {
+                logging::log!("Fetched project content successfully: {:?}", content);
                 self.project_content.1.set(content);
             }
             Err(err) => {
@@ -65,8 +61,6 @@ impl ProjectContentContext {
                 self.error.1.set(Some(err));
             }
         }
-        
-        self.is_loading.1.try_update(|v| *v = false);
     }
 
     pub async fn create_or_update_project_content(&self, text: Option<String>) {
@@ -74,12 +68,12 @@ impl ProjectContentContext {
             Some(id) => id,
             None => {
                 logging::log!("No project ID set");
-                self.error.1.set(Some("No project ID set".to_string()));
+                self.error.1.set(Some(SupabaseError::Request("No project ID set".to_string())));
                 return;
             }
         };
 
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
 
         let current_content = self.project_content.0.get();
@@ -87,13 +81,9 @@ impl ProjectContentContext {
         match current_content {
             Some(existing_content) => {
                 // Update existing content
-                let updated_content = serde_json::json!({
-                    "text": text,            
-                });
-                
-                let url_path = format!("/rest/v1/content?id=eq.{}", existing_content.id);
-                match supabase_patch::<ProjectContent, serde_json::Value>(&url_path, &updated_content).await {
+                match self.backend.update_content(ProjectContent { text, ..existing_content }).await {
                     Ok(updated) => {
+                        self.backend.invalidate(Resource::Content);
                         self.project_content.1.set(Some(updated));
                     }
                     Err(err) => {
@@ -104,13 +94,14 @@ impl ProjectContentContext {
             }
             None => {
                 // Create new content
-                let new_content = serde_json::json!({
-                    "text": text,
-                    "project_id": project_id
-                });
+                let new_content = ProjectContentDto {
+                    text,
+                    project_id,
+                };
                 
-                match supabase_post::<ProjectContent, serde_json::Value>("/rest/v1/content", &new_content).await {
+                match self.backend.create_content(new_content).await {
                     Ok(content) => {
+                        self.backend.invalidate(Resource::Content);
                         self.project_content.1.set(Some(content));
                     }
                     Err(err) => {
@@ -120,8 +111,32 @@ impl ProjectContentContext {
                 }
             }
         }
-        
-        self.is_loading.1.try_update(|v| *v = false);
+    }
+
+    /// Upload an image for the open project and return the URL to embed in its content.
+    pub async fn upload_image(&self, file: web_sys::File) -> Result<String, SupabaseError> {
+        let project_id = self.project_id.0.get_untracked()
+            .ok_or_else(|| SupabaseError::Request("No project ID set".to_string()))?;
+        self.backend.upload_image(project_id, file).await
+    }
+
+    /// Apply a change made elsewhere, received over Realtime, if it concerns the open project.
+    pub fn apply_change(&self, change: &Change) {
+        match change.kind {
+            ChangeKind::Insert | ChangeKind::Update => {
+                if let Some(content) = change.record::<ProjectContent>() {
+                    if self.project_id.0.get_untracked() == Some(content.project_id) {
+                        self.project_content.1.set(Some(content));
+                    }
+                }
+            }
+            ChangeKind::Delete => {
+                let current_id = self.project_content.0.get_untracked().map(|c| c.id);
+                if current_id.is_some() && current_id == change.old_id() {
+                    self.project_content.1.set(None);
+                }
+            }
+        }
     }
 
     pub async fn delete_project_content(&self) {
@@ -133,12 +148,12 @@ impl ProjectContentContext {
             }
         };
 
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
         
-        let url_path = format!("/rest/v1/content?id=eq.{}", content.id);
-        match supabase_delete(&url_path).await {
+        match self.backend.delete_content(content.id).await {
             Ok(_) => {
+                self.backend.invalidate(Resource::Content);
                 self.project_content.1.set(None);
             }
             Err(err) => {
@@ -146,12 +161,10 @@ impl ProjectContentContext {
                 self.error.1.set(Some(err));
             }
         }
-        
-        self.is_loading.1.try_update(|v| *v = false);
     }
 }#[component]
 pub fn ProjectContentContextProvider(children: Children) -> impl IntoView {
-    let content_context = Arc::new(ProjectContentContext::new());
+    let content_context = Arc::new(ProjectContentContext::new(use_backend()));
    
     provide_context(content_context);
 
@@ -179,8 +192,10 @@ pub fn ProjectContentRoute( children: Children) -> impl IntoView {
         if let Ok(id) = current_project_id.parse::<i64>() {
             project_content_context.set_project_id(id);
             
+            // Cancelled when the project changes again or the route unmounts,
+            // so a slow response cannot overwrite the newer project's content
             let context = project_content_context.clone();
-            spawn_local(async move {
+            spawn_cancellable(async move {
                 context.fetch_project_content().await;
             });
         } else {
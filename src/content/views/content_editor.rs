@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use leptos::{prelude::*, reactive::spawn_local};
 
-use crate::{content::{content_context::{use_project_content, ProjectContentContext}, model::ProjectContent}, shared::data_state_model::{DataState, DataHandler, MarkdownHandler}, ui::text_editor::text_editor_view::TextEditorView};
+use crate::{content::{content_context::{use_project_content, ProjectContentContext}, model::ProjectContent}, shared::data_state_model::{DataState, DataHandler, MarkdownHandler}, ui::text_editor::{editor_text_area::ImageUpload, text_editor_view::TextEditorView}};
 
 
 impl DataHandler for DataState<ProjectContent,ProjectContentContext> {
@@ -160,11 +160,18 @@ pub fn ContentEditor(
 
     let project_content_state_clone = Arc::new(content_state.clone()); 
 
+    // Images go to a folder of the open project
+    let upload_image: ImageUpload = Arc::new(move |file| {
+        let context = context.clone();
+        Box::pin(async move { context.upload_image(file).await.map_err(|e| e.to_string()) })
+    });
+
     view! {
         <div class="text-black h-[600px] ">
             <TextEditorView 
                 data_state=(*project_content_state_clone).clone()
                 field_name="text".to_string()
+                upload_image=upload_image
             />
         </div>
     }
@@ -1,6 +1,6 @@
 use leptos::{prelude::*, reactive::spawn_local};
 
-use crate::{content::{content_context::use_project_content, views::content_editor::ContentEditor}, ui::PrimaryButton};
+use crate::{content::{content_context::use_project_content, views::content_editor::ContentEditor}, ui::{error::ErrorMessage, PrimaryButton}};
 
 
 
@@ -10,6 +10,8 @@ pub fn ContentPage() -> impl IntoView {
     let content_context = use_project_content();
     let content_context_clone = content_context.clone();
     let content_context_clone_2 = content_context.clone();
+    let content_error = move || content_context.error.0.get()
+        .map(|err| view! { <ErrorMessage message=err.to_string() /> });
     let content =move || content_context_clone.project_content.0.get().clone();
 
     let handle_create_new_content = move || {
@@ -24,6 +26,7 @@ pub fn ContentPage() -> impl IntoView {
 
     view! {
         <div class="text-black ">
+        {content_error}
         {
             move || {
                
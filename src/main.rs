@@ -5,6 +5,7 @@ mod areas;
 mod catalog;
 mod content;
 mod auth;
+mod backend;
 mod shared;
 mod supabase;
 mod ui;
@@ -1,17 +1,41 @@
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos_router::components::Outlet;
 
+use crate::auth::{auth_context::use_auth, permissions::Permission};
 use crate::projects::views::editor::projects_list::ProjectsList;
 
 
 
 #[component]
 pub fn EditorPage() -> impl IntoView {
+    let auth = use_auth();
+    let can_manage_users = {
+        let auth = auth.clone();
+        move || auth.can(Permission::ManageUsers)
+    };
+
     view! {
         <main class="w-screen h-screen bg-white flex items-start justify-start p-8 text-[20px]" style="line-height: 1.5;">
-            <a href="/home" class="m-4 sticky top-16">
-                <img class="w-24 h-24" src="/public/logo-black@2x.svg" />
-            </a>
+            <div class="m-4 sticky top-16 flex flex-col items-center gap-2">
+                <a href="/home">
+                    <img class="w-24 h-24" src="/public/logo-black@2x.svg" />
+                </a>
+                <a href="/profile" class="text-sm text-blue-600 hover:text-blue-500">"Profile"</a>
+                {move || can_manage_users().then(|| view! {
+                    <a href="/editor/users" class="text-sm text-blue-600 hover:text-blue-500">"Users"</a>
+                })}
+                // Other open tabs are signed out too
+                <button
+                    class="text-sm text-gray-500 hover:text-gray-700"
+                    on:click=move |_| {
+                        let auth = auth.clone();
+                        spawn_local(async move { auth.sign_out().await });
+                    }
+                >
+                    "Sign out"
+                </button>
+            </div>
             <ProjectsList />
             <Outlet />
         </main>
@@ -1,5 +1,194 @@
 use serde::{Deserialize, Serialize};
 
+/// Where a project stands, stored as `planning`, `in-progress`, `on-hold` or `completed`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectStatus {
+    Planning,
+    InProgress,
+    OnHold,
+    Completed,
+}
+
+impl ProjectStatus {
+    pub const ALL: [ProjectStatus; 4] = [
+        ProjectStatus::Planning,
+        ProjectStatus::InProgress,
+        ProjectStatus::OnHold,
+        ProjectStatus::Completed,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.as_str() == value.trim())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectStatus::Planning => "planning",
+            ProjectStatus::InProgress => "in-progress",
+            ProjectStatus::OnHold => "on-hold",
+            ProjectStatus::Completed => "completed",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectStatus::Planning => "Planning",
+            ProjectStatus::InProgress => "In progress",
+            ProjectStatus::OnHold => "On hold",
+            ProjectStatus::Completed => "Completed",
+        }
+    }
+
+    /// Statuses a project may move to from this one. A completed project can only be reopened.
+    pub fn next(&self) -> &'static [ProjectStatus] {
+        match self {
+            ProjectStatus::Planning => &[ProjectStatus::InProgress, ProjectStatus::OnHold],
+            ProjectStatus::InProgress => &[ProjectStatus::OnHold, ProjectStatus::Completed],
+            ProjectStatus::OnHold => &[ProjectStatus::InProgress, ProjectStatus::Completed],
+            ProjectStatus::Completed => &[ProjectStatus::InProgress],
+        }
+    }
+
+    pub fn can_become(&self, next: ProjectStatus) -> bool {
+        self.next().contains(&next)
+    }
+}
+
+/// How far the capability has come, from research to a product.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Maturity {
+    Research,
+    #[serde(rename = "POC")]
+    Poc,
+    Product,
+}
+
+impl Maturity {
+    pub const ALL: [Maturity; 3] = [Maturity::Research, Maturity::Poc, Maturity::Product];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|maturity| maturity.as_str() == value.trim())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Maturity::Research => "Research",
+            Maturity::Poc => "POC",
+            Maturity::Product => "Product",
+        }
+    }
+
+    /// Maturity only grows, one step at a time.
+    pub fn next(&self) -> Option<Maturity> {
+        match self {
+            Maturity::Research => Some(Maturity::Poc),
+            Maturity::Poc => Some(Maturity::Product),
+            Maturity::Product => None,
+        }
+    }
+
+    pub fn can_become(&self, next: Maturity) -> bool {
+        self.next() == Some(next)
+    }
+}
+
+/// Lifecycle field a [`ProjectTransition`] records a change of.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LifecycleField {
+    Status,
+    Maturity,
+}
+
+impl LifecycleField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LifecycleField::Status => "Status",
+            LifecycleField::Maturity => "Maturity",
+        }
+    }
+
+    /// How a stored value reads on screen.
+    fn display(&self, value: &str) -> String {
+        match (self, ProjectStatus::parse(value)) {
+            (LifecycleField::Status, Some(status)) => status.label().to_string(),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// One row of the `project_transitions` log, written whenever a project's
+/// status or maturity changes. `from_value` is unset for the first value.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectTransition {
+    pub id: i64,
+    pub created_at: Option<String>,
+    pub project_id: i64,
+    pub field: LifecycleField,
+    pub from_value: Option<String>,
+    pub to_value: String,
+}
+
+impl ProjectTransition {
+    /// `In progress → Completed`, or just `Planning` for the first value.
+    pub fn describe(&self) -> String {
+        let to = self.field.display(&self.to_value);
+        match &self.from_value {
+            Some(from) => format!("{} → {}", self.field.display(from), to),
+            None => to,
+        }
+    }
+
+    /// Day of the change, `2026-10-18`.
+    pub fn date(&self) -> Option<String> {
+        let created_at = self.created_at.as_deref()?;
+        chrono::DateTime::parse_from_rfc3339(created_at)
+            .ok()
+            .map(|time| time.with_timezone(&chrono::Utc).format("%Y-%m-%d").to_string())
+    }
+}
+
+/// A status or maturity change that is allowed, before it is logged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LifecycleChange {
+    pub field: LifecycleField,
+    pub from_value: Option<String>,
+    pub to_value: String,
+}
+
+/// Check the status and maturity changes from `before` to `after` against their
+/// transitions. Setting a value for the first time is always allowed; clearing one is not.
+pub fn lifecycle_changes(before: &Project, after: &ProjectDto) -> Result<Vec<LifecycleChange>, String> {
+    let mut changes = vec![];
+    match (before.status, after.status) {
+        (from, to) if from == to => {}
+        (Some(from), Some(to)) if !from.can_become(to) => {
+            return Err(format!("A project cannot go from {} to {}", from.label(), to.label()));
+        }
+        (Some(_), None) => return Err("A project's status cannot be cleared".to_string()),
+        (from, Some(to)) => changes.push(LifecycleChange {
+            field: LifecycleField::Status,
+            from_value: from.map(|status| status.as_str().to_string()),
+            to_value: to.as_str().to_string(),
+        }),
+        (None, None) => {}
+    }
+    match (before.maturity, after.maturity) {
+        (from, to) if from == to => {}
+        (Some(from), Some(to)) if !from.can_become(to) => {
+            return Err(format!("Maturity cannot go from {} to {}", from.as_str(), to.as_str()));
+        }
+        (Some(_), None) => return Err("A project's maturity cannot be cleared".to_string()),
+        (from, Some(to)) => changes.push(LifecycleChange {
+            field: LifecycleField::Maturity,
+            from_value: from.map(|maturity| maturity.as_str().to_string()),
+            to_value: to.as_str().to_string(),
+        }),
+        (None, None) => {}
+    }
+    Ok(changes)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct Project {
@@ -8,6 +197,21 @@ pub struct Project {
     pub desc: Option<String>,
     pub created_at: Option<String>,
     pub order: Option<i32>,
+    #[serde(default)]
+    pub technologies: Vec<String>,
+    pub budget: Option<i64>,
+    pub status: Option<ProjectStatus>,
+    /// `YYYY-MM-DD`
+    pub date_started: Option<String>,
+    /// `YYYY-MM-DD`, unset while the project runs
+    pub date_ended: Option<String>,
+    /// Precision reached, as a power of ten in metres: -3 is a millimetre.
+    pub precision: Option<i32>,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    pub client: Option<String>,
+    pub industry: Option<String>,
+    pub maturity: Option<Maturity>,
 }
 
 impl Project {
@@ -15,7 +219,27 @@ impl Project {
         ProjectDto {
             title: self.title.clone(),
             desc: self.desc.clone(),
-            order: self.order.clone(),
+            order: self.order,
+            technologies: self.technologies.clone(),
+            budget: self.budget,
+            status: self.status,
+            date_started: self.date_started.clone(),
+            date_ended: self.date_ended.clone(),
+            precision: self.precision,
+            tools: self.tools.clone(),
+            client: self.client.clone(),
+            industry: self.industry.clone(),
+            maturity: self.maturity,
+        }
+    }
+
+    /// `2024-03-15 – 2024-07-30`, or `since 2024-03-15` while it runs.
+    pub fn period(&self) -> Option<String> {
+        match (&self.date_started, &self.date_ended) {
+            (Some(started), Some(ended)) => Some(format!("{} – {}", started, ended)),
+            (Some(started), None) => Some(format!("since {}", started)),
+            (None, Some(ended)) => Some(format!("until {}", ended)),
+            (None, None) => None,
         }
     }
 }
@@ -25,4 +249,161 @@ pub struct ProjectDto {
     pub title: String,
     pub desc: Option<String>,
     pub order: Option<i32>,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub technologies: Vec<String>,
+    pub budget: Option<i64>,
+    pub status: Option<ProjectStatus>,
+    pub date_started: Option<String>,
+    pub date_ended: Option<String>,
+    pub precision: Option<i32>,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    pub client: Option<String>,
+    pub industry: Option<String>,
+    pub maturity: Option<Maturity>,
+}
+
+impl ProjectDto {
+    /// The project these fields describe once stored under `id`.
+    pub fn into_project(self, id: i32, created_at: Option<String>) -> Project {
+        Project {
+            id,
+            title: self.title,
+            desc: self.desc,
+            created_at,
+            order: self.order,
+            technologies: self.technologies,
+            budget: self.budget,
+            status: self.status,
+            date_started: self.date_started,
+            date_ended: self.date_ended,
+            precision: self.precision,
+            tools: self.tools,
+            client: self.client,
+            industry: self.industry,
+            maturity: self.maturity,
+        }
+    }
+}
+
+/// Whole-unit amount with thousands separators: `120000` -> `120,000`.
+pub fn format_budget(budget: i64) -> String {
+    let digits = budget.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if budget < 0 {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projects_read_the_scheme_fields() {
+        let project: Project = serde_json::from_value(serde_json::json!({
+            "id": 3,
+            "title": "Paint pinhole",
+            "technologies": ["Computer vision", "AI Detection"],
+            "budget": 120000,
+            "status": "completed",
+            "date_started": "2023-09-10",
+            "date_ended": "2024-07-30",
+            "precision": -3,
+            "tools": ["mobile"],
+            "client": "nordex",
+            "industry": "Energy",
+            "maturity": "POC",
+        })).unwrap();
+        assert_eq!(project.status, Some(ProjectStatus::Completed));
+        assert_eq!(project.maturity, Some(Maturity::Poc));
+        assert_eq!(project.period().as_deref(), Some("2023-09-10 – 2024-07-30"));
+        assert_eq!(serde_json::to_value(project.to_dto()).unwrap()["status"], "completed");
+    }
+
+    #[test]
+    fn older_rows_have_empty_lists() {
+        let project: Project = serde_json::from_str(r#"{"id":1,"title":"Welding","desc":null,"created_at":null,"order":1}"#).unwrap();
+        assert!(project.technologies.is_empty());
+        assert_eq!(project.status, None);
+    }
+
+    #[test]
+    fn enums_parse_their_stored_names() {
+        assert_eq!(ProjectStatus::parse("on-hold"), Some(ProjectStatus::OnHold));
+        assert_eq!(ProjectStatus::parse("done"), None);
+        assert_eq!(Maturity::parse("POC"), Some(Maturity::Poc));
+    }
+
+    #[test]
+    fn statuses_follow_the_workflow() {
+        assert!(ProjectStatus::Planning.can_become(ProjectStatus::InProgress));
+        assert!(ProjectStatus::InProgress.can_become(ProjectStatus::OnHold));
+        assert!(ProjectStatus::OnHold.can_become(ProjectStatus::InProgress));
+        assert!(ProjectStatus::Completed.can_become(ProjectStatus::InProgress));
+        assert!(!ProjectStatus::Planning.can_become(ProjectStatus::Completed));
+        assert!(!ProjectStatus::Completed.can_become(ProjectStatus::OnHold));
+    }
+
+    #[test]
+    fn maturity_only_moves_forward_one_step() {
+        assert!(Maturity::Research.can_become(Maturity::Poc));
+        assert!(Maturity::Poc.can_become(Maturity::Product));
+        assert!(!Maturity::Research.can_become(Maturity::Product));
+        assert!(!Maturity::Product.can_become(Maturity::Poc));
+    }
+
+    #[test]
+    fn lifecycle_changes_are_checked_and_listed() {
+        let before = Project {
+            status: Some(ProjectStatus::InProgress),
+            maturity: None,
+            ..Default::default()
+        };
+        let after = ProjectDto {
+            status: Some(ProjectStatus::Completed),
+            maturity: Some(Maturity::Research),
+            ..before.to_dto()
+        };
+        let changes = lifecycle_changes(&before, &after).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].from_value.as_deref(), Some("in-progress"));
+        assert_eq!(changes[1].field, LifecycleField::Maturity);
+        assert_eq!(changes[1].from_value, None);
+
+        assert!(lifecycle_changes(&before, &before.to_dto()).unwrap().is_empty());
+        let skipped = ProjectDto { status: Some(ProjectStatus::Planning), ..before.to_dto() };
+        assert!(lifecycle_changes(&before, &skipped).is_err());
+        let cleared = ProjectDto { status: None, ..before.to_dto() };
+        assert!(lifecycle_changes(&before, &cleared).is_err());
+    }
+
+    #[test]
+    fn transitions_read_as_labels() {
+        let transition: ProjectTransition = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "created_at": "2026-10-18T12:00:00Z",
+            "project_id": 3,
+            "field": "status",
+            "from_value": "in-progress",
+            "to_value": "completed",
+        })).unwrap();
+        assert_eq!(transition.describe(), "In progress → Completed");
+        assert_eq!(transition.date().as_deref(), Some("2026-10-18"));
+    }
+
+    #[test]
+    fn budgets_are_grouped_by_thousands() {
+        assert_eq!(format_budget(75000), "75,000");
+        assert_eq!(format_budget(1_200_000), "1,200,000");
+        assert_eq!(format_budget(950), "950");
+    }
+}
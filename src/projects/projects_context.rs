@@ -2,15 +2,14 @@ use leptos::{
     prelude::{
         provide_context, signal, use_context, Children, Effect, Read, ReadSignal, Set, Update, WriteSignal
     },
-    task::spawn_local,
     *,
 };
 use leptos_router::hooks::use_params;
 use std::sync::{ Arc };
-use leptos::prelude::Get;
+use leptos::prelude::{Get, GetUntracked};
 
 use leptos_router::params::Params;
-use crate::{projects::model::{Project, ProjectDto}, supabase::{supabase_delete, supabase_get, supabase_patch, supabase_post}};
+use crate::{backend::{for_each_page, use_backend, Backend, Resource}, shared::{ordering::{renumber, replace_by_id}, task::{spawn_cancellable, Loading, LoadingGuard}}, projects::model::{lifecycle_changes, Project, ProjectDto}, supabase::{realtime::Change, SupabaseError}};
 
 
 #[derive(Clone)]
@@ -18,72 +17,93 @@ pub struct ProjectContext {
     pub projects: (ReadSignal<Vec<Project>>, WriteSignal<Vec<Project>>),
     pub current_project_id: (ReadSignal<Option<String>>, WriteSignal<Option<String>>),
     pub hovered_project_id: (ReadSignal<Option<String>>, WriteSignal<Option<String>>),
-    pub is_loading: (ReadSignal<bool>, WriteSignal<bool>),
-    pub error: (ReadSignal<Option<String>>, WriteSignal<Option<String>>),
-    url_path: String,
+    /// Number of projects on the server, known once the first page has loaded.
+    pub total_count: (ReadSignal<Option<usize>>, WriteSignal<Option<usize>>),
+    pub is_loading: Loading,
+    pub error: (ReadSignal<Option<SupabaseError>>, WriteSignal<Option<SupabaseError>>),
+    backend: Arc<dyn Backend>,
 }
 
 impl ProjectContext {
-    pub fn new() -> Self {
+    pub fn new(backend: Arc<dyn Backend>) -> Self {
         Self {
             projects: signal::<Vec<Project>>(vec![]),
             current_project_id: signal::<Option<String>>(None),
             hovered_project_id: signal::<Option<String>>(None),
-            is_loading: signal(false),
+            total_count: signal(None),
+            is_loading: Loading::default(),
             error: signal(None),
-            // url_path: "/rest/v1/projects?select=*".to_string(),
-            url_path: format!("/rest/v1/projects"),
+            backend,
         }
     }
 
     pub async fn fetch_projects(&self) {
-        self.is_loading.1.try_update(|v| {
-            *v = true;
-        });
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| {
             *e = None;
         });
-        let url = format!("{}?select=*", self.url_path);
-        match supabase_get::<Vec<Project>>(&url).await {
-            Ok(items) => {
-                logging::log!("Fetched projects successfully: {:?}", items);
-                self.projects.1.set(items);
+        // Pages are merged in as they arrive so long lists render incrementally;
+        // the final set drops projects that were deleted since the last fetch.
+        let mut loaded = vec![];
+        let result = for_each_page(
+            |offset, limit| self.backend.fetch_projects(offset, limit),
+            |page| {
+                self.total_count.1.set(page.total);
+                self.projects.1.update(|items| page.merge_into(items, |p| p.id));
+                loaded.extend(page.items);
+            },
+        ).await;
+        match result {
+            Ok(_) => {
+                logging::log!("Fetched {} projects successfully", loaded.len());
+                self.projects.1.set(loaded);
             }
             Err(err) => {
                 logging::log!("Error fetching projects: {}", err);
+                self.error.1.set(Some(err));
             }
-        }   
+        }
     } 
 
     pub async fn add_project(&self, project: Project) -> Option<Project> {
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
         let new_project = ProjectDto {
-            title: project.title,
-            desc: project.desc,
-            ..ProjectDto::default()
+            order: None,
+            ..project.to_dto()
         };
-        match supabase_post::<Project, ProjectDto>(&format!("{}", self.url_path),&new_project).await  {
+        match self.backend.create_project(new_project).await  {
             Ok(item) => {                         
+                self.backend.invalidate(Resource::Projects);
                 self.projects.1.update(|items| {
                     items.push(item.clone());
                 });
+                self.total_count.1.update(|total| *total = total.map(|t| t + 1));
                 Some(item)
             }
             Err(err) => {
                 logging::log!("Error creating project: {}", err);
+                self.error.1.set(Some(err));
                 None
             }
         }
     }
 
     pub async fn update_project(&self, project: Project) {
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
         let project_dto = project.to_dto();
         let id = project.id.clone();
-        match supabase_patch::<Project, ProjectDto>(&format!("{}?id=eq.{}", self.url_path, project.id),&project_dto).await  {
+        // Refuse a disallowed status or maturity change here too, so it is not queued offline
+        if let Some(stored) = self.projects.0.get_untracked().into_iter().find(|p| p.id == id) {
+            if let Err(message) = lifecycle_changes(&stored, &project_dto) {
+                self.error.1.set(Some(SupabaseError::Request(message)));
+                return;
+            }
+        }
+        match self.backend.update_project(id, project_dto).await  {
             Ok(item) => {                     
+                self.backend.invalidate(Resource::Projects);
                 self.projects.1.update(|items| {
                     items.iter_mut().for_each(|i| {
                         if i.id == id {
@@ -93,31 +113,89 @@ impl ProjectContext {
                 });
             }
             Err(err) => {
-                logging::log!("Error fetching items: {}", err);
+                logging::log!("Error updating project: {}", err);
+                self.error.1.set(Some(err));
             }
         }
       
     }
 
     pub async fn delete_project(&self, project_id: i32) {
-        self.is_loading.1.try_update(|v| *v = true);
+        let _loading = LoadingGuard::new(self.is_loading);
         self.error.1.update(|e| *e = None);
         
-        match supabase_delete(&format!("{}?id=eq.{}", self.url_path, project_id)).await {
+        match self.backend.delete_project(project_id).await {
             Ok(_) => {
+                self.backend.invalidate(Resource::Projects);
+                self.backend.invalidate(Resource::Catalog);
+                self.backend.invalidate(Resource::Content);
                 self.projects.1.update(|items| {
                     items.retain(|item| item.id != project_id);
                 });
+                self.total_count.1.update(|total| *total = total.map(|t| t.saturating_sub(1)));
             }
             Err(err) => {
                 logging::log!("Error deleting project: {}", err);
                 self.error.1.set(Some(err));
             }
         }
-        
-        self.is_loading.1.try_update(|v| *v = false);
     }
 
+    /// Number the projects 1..n in the given order, writing the changed rows in one request.
+    pub async fn renumber_projects(&self, ordered_ids: Vec<i32>) {
+        let changed = renumber(&self.projects.0.get_untracked(), &ordered_ids, |p| p.id, |p| &mut p.order);
+        if changed.is_empty() {
+            return;
+        }
+        let _loading = LoadingGuard::new(self.is_loading);
+        self.error.1.set(None);
+
+        match self.backend.upsert_projects(changed).await {
+            Ok(written) => {
+                self.backend.invalidate(Resource::Projects);
+                self.projects.1.update(|items| replace_by_id(items, &written, |p| p.id));
+            }
+            Err(err) => {
+                logging::log!("Error renumbering projects: {}", err);
+                self.error.1.set(Some(err));
+            }
+        }
+    }
+
+    /// Delete the selected projects, with their catalog links and content, in one request.
+    pub async fn delete_projects(&self, project_ids: Vec<i32>) {
+        if project_ids.is_empty() {
+            return;
+        }
+        let _loading = LoadingGuard::new(self.is_loading);
+        self.error.1.set(None);
+
+        match self.backend.delete_projects(project_ids.clone()).await {
+            Ok(_) => {
+                self.backend.invalidate(Resource::Projects);
+                self.backend.invalidate(Resource::Catalog);
+                self.backend.invalidate(Resource::Content);
+                let mut removed = 0;
+                self.projects.1.update(|items| {
+                    let before = items.len();
+                    items.retain(|item| !project_ids.contains(&item.id));
+                    removed = before - items.len();
+                });
+                self.total_count.1.update(|total| *total = total.map(|t| t.saturating_sub(removed)));
+            }
+            Err(err) => {
+                logging::log!("Error deleting projects: {}", err);
+                self.error.1.set(Some(err));
+            }
+        }
+    }
+
+    /// Apply a change made elsewhere, received over Realtime.
+    pub fn apply_change(&self, change: &Change) {
+        let mut delta = 0;
+        self.projects.1.update(|items| delta = change.apply(items, |p| p.id as i64));
+        self.total_count.1.update(|total| *total = total.map(|t| (t as i64 + delta).max(0) as usize));
+    }
 
     pub fn get_project_by_id(&self, project_id: &str) -> Option<Project> {
         if let Ok(id) = project_id.parse::<i32>() {
@@ -135,7 +213,7 @@ impl ProjectContext {
 
 #[component]
 pub fn ProjectProvider(children: Children) -> impl IntoView {
-    let project_context = Arc::new(ProjectContext::new());
+    let project_context = Arc::new(ProjectContext::new(use_backend()));
     provide_context(project_context);
     children()
 }
@@ -161,8 +239,8 @@ pub fn ProjectRoute(children: Children) -> impl IntoView {
     };
     let project_context = use_project();
     let project_context_clone = project_context.clone();
-    spawn_local(async move {        
-        project_context.fetch_projects().await;       
+    spawn_cancellable(async move {
+        project_context.fetch_projects().await;
     });
 
     Effect::new(move || {
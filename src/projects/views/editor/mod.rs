@@ -1,3 +1,4 @@
 pub mod projects_list;  
 pub mod project_card;
+pub mod outbox_panel;
 pub mod project_edit_page;  
\ No newline at end of file
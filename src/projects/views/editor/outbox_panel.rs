@@ -0,0 +1,89 @@
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::backend::outbox::{blocked_by_conflict, use_outbox, EntryStatus};
+use crate::ui::button::{ButtonSize, DangerButton, SecondaryButton};
+use crate::ui::error::ErrorMessage;
+
+/// Writes that have not reached the server yet, with a way out for the ones it rejected.
+#[component]
+pub fn OutboxPanel() -> impl IntoView {
+    let outbox = use_outbox();
+
+    let cloned_outbox = outbox.clone();
+    let entries = move || cloned_outbox.entries.0.get();
+    let cloned_outbox = outbox.clone();
+    let is_replaying = move || cloned_outbox.is_replaying.get();
+
+    view! {
+        {move || {
+            let entries = entries();
+            if entries.is_empty() {
+                return view! { <div></div> }.into_any();
+            }
+
+            // Later writes wait for a rejected one, though the editor already shows them
+            let blocked = blocked_by_conflict(&entries);
+            let summary = if !blocked.is_empty() {
+                format!("{} change(s) held back until the rejected one is retried or discarded", blocked.len())
+            } else if is_replaying() {
+                format!("Syncing {} change(s)…", entries.len())
+            } else {
+                format!("{} change(s) waiting to sync", entries.len())
+            };
+            let outbox = outbox.clone();
+
+            view! {
+                <div class="flex flex-col gap-2 p-3 border border-gray-200 rounded-md text-sm">
+                    <div class=if blocked.is_empty() { "text-gray-500" } else { "text-amber-700" }>{summary}</div>
+                    {entries.into_iter().map(|entry| {
+                        let label = entry.mutation.describe();
+                        match entry.status {
+                            EntryStatus::Conflict(message) => {
+                                let retry_outbox = outbox.clone();
+                                let discard_outbox = outbox.clone();
+                                view! {
+                                    <div class="flex flex-col gap-1">
+                                        <div>{label}</div>
+                                        <ErrorMessage message=message />
+                                        <div class="flex gap-2">
+                                            <SecondaryButton
+                                                size=ButtonSize::Small
+                                                on_click=Box::new(move |_| {
+                                                    retry_outbox.retry(entry.id);
+                                                    let outbox = retry_outbox.clone();
+                                                    spawn_local(async move { outbox.replay().await });
+                                                })
+                                            >
+                                                "Retry"
+                                            </SecondaryButton>
+                                            <DangerButton
+                                                size=ButtonSize::Small
+                                                on_click=Box::new(move |_| {
+                                                    discard_outbox.discard(entry.id);
+                                                    let outbox = discard_outbox.clone();
+                                                    spawn_local(async move { outbox.replay().await });
+                                                })
+                                            >
+                                                "Discard"
+                                            </DangerButton>
+                                        </div>
+                                    </div>
+                                }.into_any()
+                            }
+                            EntryStatus::Sending => view! {
+                                <div class="text-gray-500">{label}" — sending"</div>
+                            }.into_any(),
+                            EntryStatus::Pending if blocked.contains(&entry.id) => view! {
+                                <div class="text-amber-700">{label}" — blocked, not saved yet"</div>
+                            }.into_any(),
+                            EntryStatus::Pending => view! {
+                                <div class="text-gray-500">{label}</div>
+                            }.into_any(),
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+            }.into_any()
+        }}
+    }
+}
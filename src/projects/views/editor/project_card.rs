@@ -1,16 +1,34 @@
 use leptos::prelude::*;
 
 use crate::projects::model::Project;
+use crate::ui::checkbox::Checkbox;
 
 #[component]
 pub fn ProjectsCard(
     project: Project,
     is_selected: bool,
+    /// Projects ticked for a bulk action; shows a checkbox when given.
+    #[prop(default = None)]
+    checked_ids: Option<RwSignal<Vec<i32>>>,
 ) -> impl IntoView {
+          let project_id = project.id;
           let base_class = "p-2 text-sm first:rounded-t-[4px] last:rounded-b-[4px] first:border-t border-x border-b ";
           let selected_class = if is_selected { "bg-blue-500 hover:bg-blue-600 text-white border-blue-500" } else { " hover:bg-gray-50 text-black border-gray-200" };
         view! {
-            <div class=format!("{} {}", base_class, selected_class)>
+            <div class=format!("{} {} flex gap-2 items-start", base_class, selected_class)>
+                 {checked_ids.map(|checked_ids| view! {
+                     <Checkbox
+                         class="mt-1".to_string()
+                         checked=Signal::derive(move || checked_ids.get().contains(&project_id))
+                         on_change=Box::new(move |_| checked_ids.update(|ids| {
+                             if ids.contains(&project_id) {
+                                 ids.retain(|id| *id != project_id);
+                             } else {
+                                 ids.push(project_id);
+                             }
+                         }))
+                     />
+                 })}
                  <a class="flex flex-col items-start" href=format!("/editor/{}", project.id) >
                        <div>
                          <span class="pr-1">{project.clone().order}.</span>
@@ -1,6 +1,6 @@
 use leptos::{logging, prelude::*, reactive::spawn_local};
 
-use crate::{areas::{areas_context::use_areas, model::ProjectArea, views::area_editor::AreaEditor}, catalog::catalog_context::use_catalog,  ui::{select::select::Select, button::{ButtonSize, SecondaryButton}}};
+use crate::{areas::{areas_context::use_areas, model::ProjectArea, views::area_editor::AreaEditor}, catalog::catalog_context::use_catalog,  ui::{select::select::Select, button::{ButtonSize, SecondaryButton}, error::ErrorMessage}};
 
 #[component]
 pub fn ProjectAreasEditor(
@@ -50,8 +50,15 @@ pub fn ProjectAreasEditor(
         }
     });
 
+    let catalog_error = {
+        let catalog_context = catalog_context.clone();
+        move || catalog_context.error.0.get()
+            .map(|err| view! { <ErrorMessage message=err.to_string() class="pb-2".to_string() /> })
+    };
+
     view! {
         <div>
+        {catalog_error}
         <For
             each=move || categories.get()
             key=|category| category.clone()
@@ -151,27 +158,20 @@ fn CategorySection(
                                 move |selected: String| {
                                     logging::log!("Selected area title: {}", selected);
                                     // let selected = selected.split(" - ").nth(1).unwrap_or(&selected).to_string();
-                                    let current_areas = project_areas.get();
-                                    if current_areas.contains(&selected) {
-                                        if let Some(area) = all_areas.get().iter().find(|area| area.title == selected) {
-                                            let catalog_context_for_remove = catalog_context.clone();
-                                            let area_id = area.id;
-                                            spawn_local(async move {
-                                                if let Err(e) = catalog_context_for_remove.remove_project_relations(project_id as i64, area_id).await {
-                                                    logging::log!("Error removing area relation: {}", e);
-                                                }
-                                            });
+                                    if let Some(area) = all_areas.get().iter().find(|area| area.title == selected) {
+                                        // Toggle the area, then save the project's whole set of areas at once
+                                        let mut area_ids = catalog_context.get_project_areas_ids(project_id as i64);
+                                        if area_ids.contains(&area.id) {
+                                            area_ids.retain(|id| *id != area.id);
+                                        } else {
+                                            area_ids.push(area.id);
                                         }
-                                    } else {
-                                       if let Some(area) = all_areas.get().iter().find(|area| area.title == selected) {
-                                           let catalog_context_for_add = catalog_context.clone();
-                                           let area_id = area.id;
-                                           spawn_local(async move {
-                                               if let Err(e) = catalog_context_for_add.add_project_area_relation(project_id as i64, area_id).await {
-                                                   logging::log!("Error adding area relation: {}", e);
-                                               }
-                                           });
-                                       }
+                                        let catalog_context = catalog_context.clone();
+                                        spawn_local(async move {
+                                            if let Err(e) = catalog_context.set_project_areas(project_id as i64, area_ids).await {
+                                                logging::log!("Error setting project areas: {}", e);
+                                            }
+                                        });
                                     }
                                 }
                             },
@@ -3,16 +3,78 @@ use leptos::task::spawn_local;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::auth::{auth_context::use_auth, permissions::Permission};
 use crate::content::views::content_page::ContentPage;
 use crate::projects::projects_context::use_project;
-use crate::projects::model::Project;
+use crate::projects::model::{Maturity, Project, ProjectStatus};
 use crate::projects::views::editor::project_edit_page::project_areas_editor::ProjectAreasEditor;
 use crate::shared::data_state_model::DataState;
 use crate::ui::button::DangerButton;
+use crate::ui::error::ErrorMessage;
 use crate::ui::form::form_input_field::InputField;
+use crate::ui::form::form_multi_select_field::{join_list, split_list, MultiSelectField};
+use crate::ui::form::form_select_field::SelectField;
 use crate::ui::form::form_text_area::FormTextArea;
 use crate::ui::tabs::Tabs;
 
+/// Fields of the project form. Each is edited as text, see `field_value`.
+const PROJECT_FIELDS: [&str; 13] = [
+    "title", "desc", "order", "technologies", "budget", "status", "date_started",
+    "date_ended", "precision", "tools", "client", "industry", "maturity",
+];
+
+/// The text a form field starts with; empty for a new project.
+fn field_value(project: Option<&Project>, field_name: &str) -> String {
+    let Some(project) = project else {
+        return String::new();
+    };
+    match field_name {
+        "title" => project.title.clone(),
+        "desc" => project.desc.clone().unwrap_or_default(),
+        "order" => project.order.unwrap_or_default().to_string(),
+        "technologies" => join_list(&project.technologies),
+        "budget" => project.budget.map(|budget| budget.to_string()).unwrap_or_default(),
+        "status" => project.status.map(|status| status.as_str().to_string()).unwrap_or_default(),
+        "date_started" => project.date_started.clone().unwrap_or_default(),
+        "date_ended" => project.date_ended.clone().unwrap_or_default(),
+        "precision" => project.precision.map(|precision| precision.to_string()).unwrap_or_default(),
+        "tools" => join_list(&project.tools),
+        "client" => project.client.clone().unwrap_or_default(),
+        "industry" => project.industry.clone().unwrap_or_default(),
+        "maturity" => project.maturity.map(|maturity| maturity.as_str().to_string()).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Parse the form's text back into a project. Blank fields are left unset.
+fn project_from_fields(text: impl Fn(&str) -> String, id: i32, created_at: String) -> Project {
+    let optional = |field: &str| Some(text(field).trim().to_string()).filter(|value| !value.is_empty());
+    Project {
+        title: text("title"),
+        desc: Some(text("desc")),
+        order: text("order").parse().ok(),
+        technologies: split_list(&text("technologies")),
+        budget: text("budget").trim().parse().ok(),
+        status: ProjectStatus::parse(&text("status")),
+        date_started: optional("date_started"),
+        date_ended: optional("date_ended"),
+        precision: text("precision").trim().parse().ok(),
+        tools: split_list(&text("tools")),
+        client: optional("client"),
+        industry: optional("industry"),
+        maturity: Maturity::parse(&text("maturity")),
+        id,
+        created_at: Some(created_at),
+    }
+}
+
+/// Values used for a list field across all projects, for the multi-select.
+fn known_values(projects: &[Project], list: impl Fn(&Project) -> &Vec<String>) -> Vec<String> {
+    let mut values: Vec<String> = projects.iter().flat_map(|project| list(project).iter().cloned()).collect();
+    values.sort();
+    values.dedup();
+    values
+}
 
 impl DataState<Project> {
     pub fn new() -> Self {
@@ -37,38 +99,18 @@ impl DataState<Project> {
     }
 
     pub fn init_fields(&mut self) {
-        if let Some(project) = &self.init_data {
-            self.data.insert("title".to_string(), signal(project.title.clone()));
-            self.data.insert("desc".to_string(), signal(project.desc.clone().unwrap_or_default()));
-            self.data.insert("order".to_string(), signal(project.order.unwrap_or_default().to_string()));
-        } else {
-            self.data.insert("title".to_string(), signal(String::new()));
-            self.data.insert("desc".to_string(), signal(String::new()));
-            self.data.insert("order".to_string(), signal(String::new()));
+        for field in PROJECT_FIELDS {
+            self.data.insert(field.to_string(), signal(field_value(self.init_data.as_ref(), field)));
         }
     }
 
     pub fn into_data(self) -> Project {
-        Project {
-            title: self.data.get("title").map(|(r, _)| r.get()).unwrap_or_default(),
-            desc: Some(self.data.get("desc").map(|(r, _)| r.get()).unwrap_or_default()),
-            order: self.data.get("order").map(|(r, _)| r.get()).and_then(|s| s.parse().ok()),
-            id: self.id,
-            created_at: Some(self.created_at),
-        }
+        let text = |field: &str| self.data.get(field).map(|(r, _)| r.get()).unwrap_or_default();
+        project_from_fields(text, self.id, self.created_at.clone())
     }
 
     fn get_init_value(&self, field_name: &str) -> String {
-        if let Some(project) = &self.init_data {
-            match field_name {
-                "title" => project.title.clone(),
-                "desc" => project.desc.clone().unwrap_or_default(),
-                "order" => project.order.unwrap_or_default().to_string(),
-                _ => String::new(),
-            }
-        } else {
-            String::new()
-        }
+        field_value(self.init_data.as_ref(), field_name)
     }
 
     pub fn check_modified(&self) {
@@ -109,6 +151,9 @@ pub fn ProjectForm(
     #[prop(optional)] project: Option<Project>,
 ) -> impl IntoView {
     let project_context = use_project();
+    let auth = use_auth();
+    let readonly = !auth.can(Permission::Edit);
+    let can_delete = auth.can(Permission::Delete);
     let mut project_state = if let Some(project) = project.clone() {
         DataState::<Project>::from_data(Some(project))
     } else {
@@ -165,30 +210,40 @@ pub fn ProjectForm(
         }
     };
 
+    let project_error = {
+        let project_context = project_context.clone();
+        move || project_context.error.0.get()
+            .map(|err| view! { <ErrorMessage message=err.to_string() class="pb-2".to_string() /> })
+    };
+
     let handle_save_project_clone = Arc::new(handle_save_project.clone());
     let handle_create_project_clone = Arc::new(handle_create_project.clone());
 
 
     view! {
         <div class="p-6 bg-white text-black w-full h-screen flex flex-col">
+            {project_error}
             <div class="flex space-x-4">
                {
                     if let Some(project_id) = project.as_ref().map(|p| p.id) {
                         let handle_delete_project = handle_delete_project.clone();
                         view!{
                         <div class="w-full flex flex-col space-y-4">
-                            <DangerButton 
-                            on_click=move |_| {
-                                let handle_delete_project = handle_delete_project.clone();
-                                handle_delete_project(project_id);
-                            }
-                            >Delete project</DangerButton>
+                            {can_delete.then(|| view! {
+                                <DangerButton 
+                                on_click=move |_| {
+                                    let handle_delete_project = handle_delete_project.clone();
+                                    handle_delete_project(project_id);
+                                }
+                                >Delete project</DangerButton>
+                            })}
                             <div class="flex space-x-2 w-full" >
                                 <div class="w-1/6" >                                    
                                     <InputField
                                     data_state=(*project_state_clone).clone()
                                     data_handle=(*handle_save_project_clone).clone()
                                     field_name="order".to_string()
+                                    readonly=readonly
                                     />
                                 </div>
                                 <div class="grow" >
@@ -196,6 +251,7 @@ pub fn ProjectForm(
                                     data_state=(*project_state_clone).clone()
                                     data_handle=(*handle_save_project_clone).clone()
                                     field_name="title".to_string()
+                                    readonly=readonly
                                     />
                                 </div>
                             </div>
@@ -203,6 +259,12 @@ pub fn ProjectForm(
                                 data_state=(*project_state_clone).clone()
                                 data_handle=(*handle_save_project_clone).clone()
                                 field_name="desc".to_string()
+                                readonly=readonly
+                            />
+                            <ProjectDetailsFields
+                                data_state=(*project_state_clone).clone()
+                                data_handle=(*handle_save_project_clone).clone()
+                                readonly=readonly
                             />
                             <Tabs
                                 tabs_titles=vec!["Content".into(), "Areas".into()]
@@ -223,11 +285,13 @@ pub fn ProjectForm(
                                 data_state=(*project_state_clone).clone()
                                 data_handle=(*handle_create_project_clone).clone()
                                 field_name="title".to_string()
+                                readonly=readonly
                             />
                             <FormTextArea
                                 data_state=(*project_state_clone).clone()
                                 data_handle=(*handle_create_project_clone).clone()
                                 field_name="desc".to_string()
+                                readonly=readonly
                             />                           
                         </div>}.into_any()
                     }
@@ -238,3 +302,154 @@ pub fn ProjectForm(
     }
 }
 
+
+/// `current` and the values it can become, in `all`'s order; every value while unset.
+fn reachable<T: Copy + PartialEq, const N: usize>(
+    all: [T; N],
+    current: Option<T>,
+    can_become: fn(&T, T) -> bool,
+) -> impl Iterator<Item = T> {
+    all.into_iter().filter(move |&value| current.is_none_or(|current| current == value || can_become(&current, value)))
+}
+
+/// Status, dates, budget and the other descriptive fields of an existing project.
+#[component]
+fn ProjectDetailsFields(
+    data_state: DataState<Project>,
+    data_handle: impl FnMut() + 'static + Clone + Send,
+    readonly: bool,
+) -> impl IntoView {
+    let projects = use_project().projects.0;
+    let technologies = Signal::derive(move || known_values(&projects.get(), |project| &project.technologies));
+    let tools = Signal::derive(move || known_values(&projects.get(), |project| &project.tools));
+    // Only the stored value and the ones it may move to are offered, and a set value stays set
+    let stored_status = data_state.init_data.as_ref().and_then(|project| project.status);
+    let stored_maturity = data_state.init_data.as_ref().and_then(|project| project.maturity);
+    let statuses: Vec<(String, String)> = reachable(ProjectStatus::ALL, stored_status, ProjectStatus::can_become)
+        .map(|status| (status.as_str().to_string(), status.label().to_string()))
+        .collect();
+    let maturities: Vec<(String, String)> = reachable(Maturity::ALL, stored_maturity, Maturity::can_become)
+        .map(|maturity| (maturity.as_str().to_string(), maturity.as_str().to_string()))
+        .collect();
+
+    let input = |label: &'static str, field_name: &str, input_type: &str| view! {
+        <label class="flex flex-col text-sm text-gray-500">
+            {label}
+            <InputField
+                data_state=data_state.clone()
+                data_handle=data_handle.clone()
+                field_name=field_name.to_string()
+                input_type=input_type.to_string()
+                readonly=readonly
+            />
+        </label>
+    };
+
+    view! {
+        <div class="grid grid-cols-2 gap-2">
+            <label class="flex flex-col text-sm text-gray-500">
+                "Status"
+                <SelectField
+                    data_state=data_state.clone()
+                    data_handle=data_handle.clone()
+                    field_name="status".to_string()
+                    options=statuses
+                    readonly=readonly
+                    clearable=stored_status.is_none()
+                />
+            </label>
+            <label class="flex flex-col text-sm text-gray-500">
+                "Maturity"
+                <SelectField
+                    data_state=data_state.clone()
+                    data_handle=data_handle.clone()
+                    field_name="maturity".to_string()
+                    options=maturities
+                    readonly=readonly
+                    clearable=stored_maturity.is_none()
+                />
+            </label>
+            {input("Started", "date_started", "date")}
+            {input("Ended", "date_ended", "date")}
+            {input("Budget", "budget", "number")}
+            {input("Precision (10ⁿ m)", "precision", "number")}
+            {input("Client", "client", "text")}
+            {input("Industry", "industry", "text")}
+            <label class="col-span-2 flex flex-col text-sm text-gray-500">
+                "Technologies"
+                <MultiSelectField
+                    data_state=data_state.clone()
+                    data_handle=data_handle.clone()
+                    field_name="technologies".to_string()
+                    options=technologies
+                    readonly=readonly
+                />
+            </label>
+            <label class="col-span-2 flex flex-col text-sm text-gray-500">
+                "Tools"
+                <MultiSelectField
+                    data_state=data_state.clone()
+                    data_handle=data_handle.clone()
+                    field_name="tools".to_string()
+                    options=tools
+                    readonly=readonly
+                />
+            </label>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn form_fields_round_trip() {
+        let project = Project {
+            id: 3,
+            title: "Paint pinhole".to_string(),
+            desc: Some("Paint pinhole detection capability".to_string()),
+            created_at: Some("2026-10-18T12:00:00Z".to_string()),
+            order: Some(3),
+            technologies: vec!["Computer vision".to_string(), "AI Detection".to_string()],
+            budget: Some(120000),
+            status: Some(ProjectStatus::Completed),
+            date_started: Some("2023-09-10".to_string()),
+            date_ended: Some("2024-07-30".to_string()),
+            precision: Some(-3),
+            tools: vec!["mobile".to_string()],
+            client: Some("nordex".to_string()),
+            industry: Some("Energy".to_string()),
+            maturity: Some(Maturity::Poc),
+        };
+        let text = |field: &str| field_value(Some(&project), field);
+        assert_eq!(project_from_fields(text, 3, "2026-10-18T12:00:00Z".to_string()), project);
+    }
+
+    #[test]
+    fn blank_fields_are_unset() {
+        let project = project_from_fields(|_| String::new(), 0, String::new());
+        assert_eq!(project.budget, None);
+        assert_eq!(project.status, None);
+        assert_eq!(project.date_ended, None);
+        assert!(project.technologies.is_empty());
+    }
+
+    #[test]
+    fn only_reachable_lifecycle_values_are_offered() {
+        let statuses: Vec<_> = reachable(ProjectStatus::ALL, Some(ProjectStatus::OnHold), ProjectStatus::can_become).collect();
+        assert_eq!(statuses, vec![ProjectStatus::InProgress, ProjectStatus::OnHold, ProjectStatus::Completed]);
+        let maturities: Vec<_> = reachable(Maturity::ALL, Some(Maturity::Product), Maturity::can_become).collect();
+        assert_eq!(maturities, vec![Maturity::Product]);
+        assert_eq!(reachable(Maturity::ALL, None, Maturity::can_become).count(), 3);
+    }
+
+    #[test]
+    fn known_values_are_sorted_once() {
+        let projects = vec![
+            Project { technologies: vec!["LLM".to_string(), "Computer vision".to_string()], ..Default::default() },
+            Project { technologies: vec!["Computer vision".to_string()], ..Default::default() },
+        ];
+        assert_eq!(known_values(&projects, |project| &project.technologies), vec!["Computer vision", "LLM"]);
+    }
+}
@@ -1,18 +1,64 @@
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos_router::hooks::use_navigate;
 
+use crate::auth::{auth_context::use_auth, permissions::Permission};
 use crate::projects::projects_context::use_project;
+use crate::projects::views::editor::outbox_panel::OutboxPanel;
 use crate::projects::views::editor::project_card::ProjectsCard;
-use crate::ui::button::PrimaryButton;
+use crate::ui::button::{ButtonSize, DangerButton, PrimaryButton, SecondaryButton};
 
 #[component]
 pub fn ProjectsList(
 ) -> impl IntoView {
     let project_context = use_project();
+    let auth = use_auth();
+    let can_edit = auth.can(Permission::Edit);
+    let can_delete = auth.can(Permission::Delete);
     let cloned_context = project_context.clone();
     let current_project_id = move || cloned_context.current_project_id.0.get();
     let cloned_context = project_context.clone();
     let projects = move || cloned_context.projects.0.get(); 
+    let cloned_context = project_context.clone();
+    // Projects arrive a page at a time; show progress until the list is complete
+    let loading_progress = move || {
+        let loaded = cloned_context.projects.0.get().len();
+        cloned_context.total_count.0.get()
+            .filter(|total| loaded < *total)
+            .map(|total| format!("Loaded {} of {} projects…", loaded, total))
+    };
+
+    // Projects ticked for "Delete selected"
+    let checked_ids = RwSignal::new(Vec::<i32>::new());
+
+    // Number the projects 1..n in the order they are listed, fixing gaps and duplicates
+    let handle_renumber = {
+        let project_context = project_context.clone();
+        move |_| {
+            let mut ordered = project_context.projects.0.get_untracked();
+            ordered.sort_by_key(|p| (p.order, p.id));
+            let ordered_ids = ordered.iter().map(|p| p.id).collect();
+            let project_context = project_context.clone();
+            spawn_local(async move { project_context.renumber_projects(ordered_ids).await });
+        }
+    };
+
+    let handle_delete_checked = {
+        let project_context = project_context.clone();
+        move |_| {
+            let ids = checked_ids.get_untracked();
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message(&format!("Delete {} project(s)?", ids.len())).ok())
+                .unwrap_or(false);
+            if confirmed {
+                let project_context = project_context.clone();
+                spawn_local(async move {
+                    project_context.delete_projects(ids).await;
+                    checked_ids.set(vec![]);
+                });
+            }
+        }
+    };
 
     let navigate = use_navigate();
     let navigate_create = navigate.clone();
@@ -26,12 +72,37 @@ pub fn ProjectsList(
 
     view! {
         <div class="flex flex-col p-4 gap-4 w-[400px]" >
-            <div class="flex justify-between items-center">
-                <PrimaryButton on_click=handle_create >
-                    "Create new project"
-                </PrimaryButton>
-            </div>
+            {can_edit.then(|| view! {
+                <div class="flex justify-between items-center">
+                    <PrimaryButton on_click=handle_create >
+                        "Create new project"
+                    </PrimaryButton>
+                    <SecondaryButton size=ButtonSize::Small on_click=handle_renumber>
+                        "Renumber"
+                    </SecondaryButton>
+                </div>
+            })}
+            <a href="/editor/areas" class="text-sm text-gray-600 underline">"Manage areas"</a>
+
+            {move || {
+                let count = checked_ids.get().len();
+                let handle_delete_checked = handle_delete_checked.clone();
+                (count > 0).then(|| view! {
+                    <div class="flex justify-between items-center text-sm">
+                        <span>{format!("{} selected", count)}</span>
+                        <DangerButton size=ButtonSize::Small on_click=handle_delete_checked>
+                            "Delete selected"
+                        </DangerButton>
+                    </div>
+                })
+            }}
+
+            <OutboxPanel />
             
+            {move || loading_progress().map(|progress| view! {
+                <div class="text-sm text-gray-500">{progress}</div>
+            })}
+
             <div class="">
                 {move || {
                     let mut project_list = projects();
@@ -48,7 +119,7 @@ pub fn ProjectsList(
                         project_list.into_iter().map(|project| {
                             let is_project_selected = current_project_id() == Some(project.id.to_string());
                             view! {
-                               <ProjectsCard project=project is_selected=is_project_selected />
+                               <ProjectsCard project=project is_selected=is_project_selected checked_ids=can_delete.then_some(checked_ids) />
                             }
                         }).collect::<Vec<_>>().into_any()
                     }
@@ -2,4 +2,5 @@
 pub mod projects_list_view; 
 pub mod project_view; 
 pub mod project_header; 
-pub mod project_areas; 
\ No newline at end of file
+pub mod project_areas;
+pub mod project_timeline;
@@ -1,7 +1,7 @@
 use leptos::{html::Div, prelude::*};
 use leptos_use::use_element_visibility;
 
-use crate::projects::model::Project;
+use crate::projects::model::{format_budget, Project};
 
 
 
@@ -32,8 +32,9 @@ pub fn ProjectHeader(
                 move || if let Some(proj) = project.get() {
                     view! {
                         <>
-                            <span class="pr-2">{proj.title}</span>           
-                            <div class="text-gray-400 h-32px">{proj.desc}</div>
+                            <span class="pr-2">{proj.title.clone()}</span>           
+                            <div class="text-gray-400 h-32px">{proj.desc.clone()}</div>
+                            <ProjectFacts project=proj />
                         </>
                     }.into_any()
                 } else {
@@ -47,4 +48,33 @@ pub fn ProjectHeader(
         </div>
          
     }
-}
\ No newline at end of file
+}
+
+/// One line of the project's status, maturity, dates and other facts, then its
+/// technologies and tools. Facts that are not set are left out.
+#[component]
+fn ProjectFacts(project: Project) -> impl IntoView {
+    let facts: Vec<String> = [
+        project.status.map(|status| status.label().to_string()),
+        project.maturity.map(|maturity| maturity.as_str().to_string()),
+        project.period(),
+        project.client.clone(),
+        project.industry.clone(),
+        project.budget.map(|budget| format!("Budget {}", format_budget(budget))),
+    ].into_iter().flatten().collect();
+    let tags: Vec<String> = project.technologies.iter().chain(project.tools.iter()).cloned().collect();
+
+    view! {
+        <div class="flex flex-wrap gap-x-4 text-sm text-gray-500">
+            {facts.into_iter().map(|fact| view! { <span>{fact}</span> }).collect::<Vec<_>>()}
+            {project.precision.map(|precision| view! {
+                <span>"Precision " <var>"10"<sup>{precision}</sup></var>" m"</span>
+            })}
+        </div>
+        <div class="flex flex-wrap gap-1 pt-2">
+            {tags.into_iter().map(|tag| view! {
+                <span class="p-1 px-2 rounded-[6px] tracking-wider bg-gray-100 text-[10px] uppercase">{tag}</span>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
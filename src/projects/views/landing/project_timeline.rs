@@ -0,0 +1,47 @@
+use leptos::{logging, prelude::*, task::spawn_local};
+
+use crate::{backend::use_backend, projects::model::{Project, ProjectTransition}};
+
+/// Status and maturity changes of the project, oldest first. Reloads when either
+/// changes, since every change adds a row to the log.
+#[component]
+pub fn ProjectTimeline(
+    project: ReadSignal<Option<Project>>
+) -> impl IntoView {
+    let backend = use_backend();
+    let (transitions, set_transitions) = signal(Vec::<ProjectTransition>::new());
+    let lifecycle = Memo::new(move |_| project.get().map(|project| (project.id, project.status, project.maturity)));
+
+    Effect::new(move |_| {
+        let Some((project_id, _, _)) = lifecycle.get() else {
+            set_transitions.set(vec![]);
+            return;
+        };
+        let backend = backend.clone();
+        spawn_local(async move {
+            match backend.fetch_transitions(project_id as i64).await {
+                Ok(items) => set_transitions.set(items),
+                Err(err) => logging::log!("Error fetching transitions: {}", err),
+            }
+        });
+    });
+
+    view! {
+        <Show when=move || !transitions.get().is_empty()>
+            <ol class="border-l border-gray-200 mx-4 mb-4 text-sm">
+                <For
+                    each=move || transitions.get()
+                    key=|transition| transition.id
+                    let:transition
+                >
+                    <li class="relative pl-4 pb-2">
+                        <span class="absolute -left-[5px] top-[6px] h-[9px] w-[9px] rounded-full bg-gray-300" />
+                        <span class="text-gray-400 pr-2">{transition.date()}</span>
+                        <span class="text-gray-500 pr-1">{transition.field.label()}</span>
+                        <span>{transition.describe()}</span>
+                    </li>
+                </For>
+            </ol>
+        </Show>
+    }
+}
@@ -1,6 +1,6 @@
 use leptos::prelude::*;
 
-use crate::{content::views::content_view::ContentView, projects::{projects_context::use_project, views::landing::{project_areas::ProjectAreas, project_header::ProjectHeader}}, ui::CloseButton};
+use crate::{content::views::content_view::ContentView, projects::{projects_context::use_project, views::landing::{project_areas::ProjectAreas, project_header::ProjectHeader, project_timeline::ProjectTimeline}}, ui::CloseButton};
 
 
 
@@ -21,6 +21,7 @@ pub fn ProjectView() -> impl IntoView {
         <div class=" flex w-full flex-col border h-full overflow-y-auto rounded-lg bg-white shadow-md">
             <ProjectAreas project=project_signal />
             <ProjectHeader project=project_signal />
+            <ProjectTimeline project=project_signal />
             <ContentView />
         </div>
         <div class="absolute top-2 right-2">
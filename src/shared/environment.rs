@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use gloo_net::http::Request;
+use leptos::prelude::use_context;
+use serde::Deserialize;
+
+use crate::supabase::SupabaseConfig;
+
+const CONFIG_PATH: &str = "/config.json";
+
+/// Where the contexts read and write their data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    #[default]
+    Supabase,
+    Memory,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Profile {
+    #[serde(default)]
+    backend: BackendKind,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    api_key: String,
+    /// Realtime websocket URL, when it is not the project's own.
+    #[serde(default)]
+    realtime_url: Option<String>,
+    /// GoTrue base URL, when auth is served by something other than the project,
+    /// e.g. `http://localhost:9999` for a local stand-in.
+    #[serde(default)]
+    auth_url: Option<String>,
+    /// OAuth providers enabled in the project, shown as sign-in buttons.
+    #[serde(default)]
+    oauth_providers: Vec<String>,
+}
+
+/// Shape of `/config.json`.
+#[derive(Debug, Clone, Deserialize)]
+struct ConfigFile {
+    default_profile: String,
+    profiles: HashMap<String, Profile>,
+}
+
+/// Runtime settings selected at startup, so one WASM build can serve
+/// local, staging and production deployments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Environment {
+    pub profile: String,
+    pub backend: BackendKind,
+    pub supabase: SupabaseConfig,
+    pub oauth_providers: Vec<String>,
+}
+
+impl Environment {
+    /// Pick a profile from a `/config.json` document; `profile` overrides `default_profile`.
+    pub fn from_config_json(json: &str, profile: Option<&str>) -> Result<Self, String> {
+        let config: ConfigFile = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid {}: {}", CONFIG_PATH, e))?;
+        let name = profile.unwrap_or(&config.default_profile).to_string();
+        let selected = config.profiles
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| format!("Profile '{}' not found in {}", name, CONFIG_PATH))?;
+
+        if selected.backend == BackendKind::Supabase && (selected.url.is_empty() || selected.api_key.is_empty()) {
+            return Err(format!("Profile '{}' needs both url and api_key", name));
+        }
+
+        Ok(Self {
+            profile: name,
+            backend: selected.backend,
+            supabase: SupabaseConfig::new(&selected.url, &selected.api_key)
+                .with_realtime_url(selected.realtime_url)
+                .with_auth_url(selected.auth_url),
+            oauth_providers: selected.oauth_providers,
+        })
+    }
+
+    /// Load the environment: `<meta name="supabase-url">` and `<meta name="supabase-api-key">`
+    /// win when both are present, otherwise `/config.json` is fetched and the profile named by
+    /// `<meta name="spiral-profile">` (or the file's `default_profile`) is used.
+    pub async fn load() -> Result<Self, String> {
+        if let (Some(url), Some(api_key)) = (meta_content("supabase-url"), meta_content("supabase-api-key")) {
+            return Ok(Self {
+                profile: "meta".to_string(),
+                backend: BackendKind::Supabase,
+                supabase: SupabaseConfig::new(&url, &api_key),
+                oauth_providers: vec![],
+            });
+        }
+
+        let resp = Request::get(CONFIG_PATH)
+            .send()
+            .await
+            .map_err(|e| format!("Could not load {}: {}", CONFIG_PATH, e))?;
+        if !resp.ok() {
+            return Err(format!("Could not load {}: HTTP {}", CONFIG_PATH, resp.status()));
+        }
+        let json = resp.text().await.map_err(|e| e.to_string())?;
+
+        Self::from_config_json(&json, meta_content("spiral-profile").as_deref())
+    }
+}
+
+fn meta_content(name: &str) -> Option<String> {
+    web_sys::window()?
+        .document()?
+        .query_selector(&format!("meta[name=\"{}\"]", name))
+        .ok()??
+        .get_attribute("content")
+        .filter(|content| !content.is_empty())
+}
+
+pub fn use_environment() -> Environment {
+    use_context::<Environment>().expect(
+        "Environment not found. Make sure the app was started through App."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "default_profile": "production",
+        "profiles": {
+            "local": { "backend": "memory" },
+            "stand-in": { "url": "http://localhost:54321", "api_key": "local", "realtime_url": "ws://localhost:4000/socket", "auth_url": "http://localhost:9999/", "oauth_providers": ["github"] },
+            "production": { "url": "https://prod.supabase.co", "api_key": "sb_publishable_prod" }
+        }
+    }"#;
+
+    #[test]
+    fn uses_default_profile() {
+        let env = Environment::from_config_json(CONFIG, None).unwrap();
+        assert_eq!(env.profile, "production");
+        assert_eq!(env.backend, BackendKind::Supabase);
+        assert_eq!(env.supabase.url, "https://prod.supabase.co");
+        assert!(!env.supabase.is_jwt_key());
+    }
+
+    #[test]
+    fn profile_override_selects_memory_backend() {
+        let env = Environment::from_config_json(CONFIG, Some("local")).unwrap();
+        assert_eq!(env.backend, BackendKind::Memory);
+    }
+
+    #[test]
+    fn realtime_endpoint_follows_project_url_unless_overridden() {
+        let env = Environment::from_config_json(CONFIG, None).unwrap();
+        assert_eq!(
+            env.supabase.realtime_endpoint(),
+            "wss://prod.supabase.co/realtime/v1/websocket?apikey=sb_publishable_prod&vsn=1.0.0"
+        );
+        let env = Environment::from_config_json(CONFIG, Some("stand-in")).unwrap();
+        assert_eq!(env.supabase.realtime_endpoint(), "ws://localhost:4000/socket?apikey=local&vsn=1.0.0");
+    }
+
+    #[test]
+    fn auth_endpoint_follows_project_url_unless_overridden() {
+        let env = Environment::from_config_json(CONFIG, None).unwrap();
+        assert_eq!(env.supabase.auth_endpoint("/auth/v1/authorize"), "https://prod.supabase.co/auth/v1/authorize");
+        assert!(env.oauth_providers.is_empty());
+        let env = Environment::from_config_json(CONFIG, Some("stand-in")).unwrap();
+        assert_eq!(env.supabase.auth_endpoint("/auth/v1/authorize"), "http://localhost:9999/authorize");
+        assert_eq!(env.oauth_providers, vec!["github".to_string()]);
+    }
+
+    #[test]
+    fn unknown_profile_is_an_error() {
+        let err = Environment::from_config_json(CONFIG, Some("staging")).unwrap_err();
+        assert!(err.contains("staging"));
+    }
+
+    #[test]
+    fn supabase_profile_requires_keys() {
+        let config = r#"{ "default_profile": "broken", "profiles": { "broken": { "url": "https://x.supabase.co" } } }"#;
+        assert!(Environment::from_config_json(config, None).is_err());
+    }
+}
@@ -1 +1,4 @@
-pub mod data_state_model;
\ No newline at end of file
+pub mod data_state_model;
+pub mod environment;
+pub mod ordering;
+pub mod task;
@@ -0,0 +1,68 @@
+/// Give the items listed in `ordered_ids` the orders `1..=n`, in that sequence.
+///
+/// Returns only the items whose order changed, so a bulk write sends no more rows than needed.
+/// Ids that are not in `items` are skipped.
+pub fn renumber<T, K>(
+    items: &[T],
+    ordered_ids: &[K],
+    id: impl Fn(&T) -> K,
+    order: impl Fn(&mut T) -> &mut Option<i32>,
+) -> Vec<T>
+where
+    T: Clone,
+    K: PartialEq,
+{
+    let mut changed = vec![];
+    for (position, ordered_id) in ordered_ids.iter().enumerate() {
+        let Some(item) = items.iter().find(|item| id(item) == *ordered_id) else { continue };
+        let mut item = item.clone();
+        let new_order = Some(position as i32 + 1);
+        if *order(&mut item) != new_order {
+            *order(&mut item) = new_order;
+            changed.push(item);
+        }
+    }
+    changed
+}
+
+/// Replace items with the written copies that share their id.
+pub fn replace_by_id<T, K>(items: &mut [T], written: &[T], id: impl Fn(&T) -> K)
+where
+    T: Clone,
+    K: PartialEq,
+{
+    for item in items.iter_mut() {
+        if let Some(updated) = written.iter().find(|w| id(w) == id(item)) {
+            *item = updated.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Row {
+        id: i64,
+        order: Option<i32>,
+    }
+
+    fn row(id: i64, order: Option<i32>) -> Row {
+        Row { id, order }
+    }
+
+    #[test]
+    fn renumber_returns_only_changed_rows() {
+        let rows = vec![row(1, Some(1)), row(2, Some(5)), row(3, None)];
+        let changed = renumber(&rows, &[1, 3, 2, 9], |r| r.id, |r| &mut r.order);
+        assert_eq!(changed, vec![row(3, Some(2)), row(2, Some(3))]);
+    }
+
+    #[test]
+    fn replace_by_id_swaps_in_written_rows() {
+        let mut rows = vec![row(1, None), row(2, None)];
+        replace_by_id(&mut rows, &[row(2, Some(1))], |r| r.id);
+        assert_eq!(rows, vec![row(1, None), row(2, Some(1))]);
+    }
+}
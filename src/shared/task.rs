@@ -0,0 +1,69 @@
+use std::future::Future;
+
+use futures::future::{AbortHandle, Abortable};
+use leptos::prelude::{on_cleanup, Get, GetUntracked, RwSignal, Update};
+use leptos::task::spawn_local;
+
+/// Spawn a task that is aborted when the current reactive owner is cleaned up:
+/// when the route or component unmounts, or before an effect runs again.
+///
+/// Dropping the task drops its pending requests, which aborts them in the browser.
+pub fn spawn_cancellable(task: impl Future<Output = ()> + 'static) -> AbortHandle {
+    let (handle, registration) = AbortHandle::new_pair();
+    spawn_local(async move {
+        let _ = Abortable::new(task, registration).await;
+    });
+
+    let on_unmount = handle.clone();
+    on_cleanup(move || on_unmount.abort());
+    handle
+}
+
+/// Number of operations of a context in flight. It reads as loading while any of
+/// them runs, so a fetch that ends first does not hide a mutation still running.
+#[derive(Clone, Copy, Default)]
+pub struct Loading(RwSignal<usize>);
+
+impl Loading {
+    pub fn get(&self) -> bool {
+        self.0.get() > 0
+    }
+
+    pub fn get_untracked(&self) -> bool {
+        self.0.get_untracked() > 0
+    }
+}
+
+/// Counts as one operation of a [`Loading`] for as long as it is alive, so a
+/// cancelled fetch does not leave `is_loading` stuck at `true`.
+pub struct LoadingGuard(Loading);
+
+impl LoadingGuard {
+    pub fn new(loading: Loading) -> Self {
+        loading.0.try_update(|active| *active += 1);
+        Self(loading)
+    }
+}
+
+impl Drop for LoadingGuard {
+    fn drop(&mut self) {
+        self.0.0.try_update(|active| *active = active.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loading_lasts_until_the_last_guard_drops() {
+        let loading = Loading::default();
+        let fetch = LoadingGuard::new(loading);
+        let mutation = LoadingGuard::new(loading);
+
+        drop(fetch);
+        assert!(loading.get_untracked());
+        drop(mutation);
+        assert!(!loading.get_untracked());
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::channel::oneshot;
+
+use crate::supabase::{client::RawResponse, SupabaseError};
+
+type FlightResult = Result<RawResponse, SupabaseError>;
+
+/// How long cached GET responses are served.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachePolicy {
+    /// Responses younger than this are served without a request.
+    pub fresh_ms: f64,
+    /// Older responses, up to this age, are served while a request refreshes them.
+    pub stale_ms: f64,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            fresh_ms: 30_000.0,
+            stale_ms: 300_000.0,
+        }
+    }
+}
+
+/// Result of looking a request up in the cache.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lookup {
+    Fresh(RawResponse),
+    /// Usable, but should be revalidated in the background.
+    Stale(RawResponse),
+    Miss,
+}
+
+#[derive(Debug)]
+struct Entry {
+    table: String,
+    response: RawResponse,
+    stored_at: f64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+    in_flight: HashMap<String, Vec<oneshot::Sender<FlightResult>>>,
+}
+
+/// Cache of successful GET responses keyed by request, shared by clones of the client.
+///
+/// Identical requests that are already in flight are joined instead of being sent again.
+#[derive(Debug, Clone, Default)]
+pub struct RequestCache {
+    policy: CachePolicy,
+    state: Arc<Mutex<CacheState>>,
+}
+
+/// Either send the request and share its result, or wait for the one already in flight.
+pub enum Flight {
+    Leader(FlightGuard),
+    Follower(oneshot::Receiver<FlightResult>),
+}
+
+impl RequestCache {
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn lookup(&self, key: &str, now: f64) -> Lookup {
+        match self.state().entries.get(key) {
+            Some(entry) if now - entry.stored_at < self.policy.fresh_ms => Lookup::Fresh(entry.response.clone()),
+            Some(entry) if now - entry.stored_at < self.policy.stale_ms => Lookup::Stale(entry.response.clone()),
+            _ => Lookup::Miss,
+        }
+    }
+
+    pub fn store(&self, key: &str, table: &str, response: RawResponse, now: f64) {
+        self.state().entries.insert(key.to_string(), Entry {
+            table: table.to_string(),
+            response,
+            stored_at: now,
+        });
+    }
+
+    /// Drop every cached response read from `table`.
+    pub fn invalidate(&self, table: &str) {
+        self.state().entries.retain(|_, entry| entry.table != table);
+    }
+
+    pub fn clear(&self) {
+        self.state().entries.clear();
+    }
+
+    pub fn join(&self, key: &str) -> Flight {
+        let mut state = self.state();
+        match state.in_flight.get_mut(key) {
+            Some(waiters) => {
+                let (sender, receiver) = oneshot::channel();
+                waiters.push(sender);
+                Flight::Follower(receiver)
+            }
+            None => {
+                state.in_flight.insert(key.to_string(), vec![]);
+                Flight::Leader(FlightGuard {
+                    cache: self.clone(),
+                    key: key.to_string(),
+                })
+            }
+        }
+    }
+}
+
+/// Held by the task sending a shared request. If it is dropped without [`FlightGuard::finish`]
+/// (the task was cancelled), waiting requests are released to send their own.
+pub struct FlightGuard {
+    cache: RequestCache,
+    key: String,
+}
+
+impl FlightGuard {
+    /// Hand the result to every request that joined this one.
+    pub fn finish(self, result: &FlightResult) {
+        let waiters = self.cache.state().in_flight.remove(&self.key).unwrap_or_default();
+        for waiter in waiters {
+            let _ = waiter.send(result.clone());
+        }
+    }
+}
+
+impl Drop for FlightGuard {
+    fn drop(&mut self) {
+        self.cache.state().in_flight.remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    fn response(text: &str) -> RawResponse {
+        RawResponse {
+            status: 200,
+            content_range: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn entries_go_stale_then_expire() {
+        let cache = RequestCache::default();
+        cache.store("projects?select=*", "projects", response("[]"), 0.0);
+
+        assert_eq!(cache.lookup("projects?select=*", 1_000.0), Lookup::Fresh(response("[]")));
+        assert_eq!(cache.lookup("projects?select=*", 60_000.0), Lookup::Stale(response("[]")));
+        assert_eq!(cache.lookup("projects?select=*", 600_000.0), Lookup::Miss);
+    }
+
+    #[test]
+    fn invalidate_drops_only_that_table() {
+        let cache = RequestCache::default();
+        cache.store("projects?select=*", "projects", response("[]"), 0.0);
+        cache.store("catalog?select=*", "catalog", response("[]"), 0.0);
+
+        cache.invalidate("projects");
+        assert_eq!(cache.lookup("projects?select=*", 0.0), Lookup::Miss);
+        assert_ne!(cache.lookup("catalog?select=*", 0.0), Lookup::Miss);
+    }
+
+    #[test]
+    fn identical_requests_share_one_flight() {
+        let cache = RequestCache::default();
+        let Flight::Leader(guard) = cache.join("areas") else { panic!("expected leader") };
+        let Flight::Follower(waiter) = cache.join("areas") else { panic!("expected follower") };
+
+        guard.finish(&Ok(response("[1]")));
+        assert_eq!(block_on(waiter).unwrap(), Ok(response("[1]")));
+        assert!(matches!(cache.join("areas"), Flight::Leader(_)));
+    }
+
+    #[test]
+    fn cancelled_leader_releases_followers() {
+        let cache = RequestCache::default();
+        let leader = cache.join("areas");
+        let Flight::Follower(waiter) = cache.join("areas") else { panic!("expected follower") };
+
+        drop(leader);
+        assert!(block_on(waiter).is_err());
+        assert!(matches!(cache.join("areas"), Flight::Leader(_)));
+    }
+}
@@ -0,0 +1,530 @@
+use std::sync::{Arc, RwLock};
+
+use futures::future::{select, Either, LocalBoxFuture};
+use futures::pin_mut;
+use gloo_net::http::{Method, Request, RequestBuilder, Response};
+use gloo_timers::future::TimeoutFuture;
+use js_sys::Date;
+use leptos::prelude::{provide_context, use_context, Children};
+use leptos::task::spawn_local;
+use leptos::*;
+use serde::{de::DeserializeOwned, Serialize};
+use web_sys::{AbortController, AbortSignal, Blob};
+
+use crate::supabase::{
+    cache::{Flight, Lookup, RequestCache},
+    page::content_range_total,
+    Page, Query, RetryPolicy, SupabaseConfig, SupabaseError,
+};
+
+/// Asked for a new access token when a data request is rejected with 401.
+/// Resolves to whether the session was refreshed, in which case the request is sent again.
+pub type SessionRefresher = Arc<dyn Fn() -> LocalBoxFuture<'static, bool> + Send + Sync>;
+
+/// Supabase client shared through Leptos context.
+///
+/// Owns the project URL, the API key and the signed-in user's access token,
+/// so every data request is built the same way and can be pointed at a
+/// different project (staging, a local stand-in) by providing another client.
+#[derive(Clone)]
+pub struct SupabaseClient {
+    config: SupabaseConfig,
+    retry: RetryPolicy,
+    cache: RequestCache,
+    access_token: Arc<RwLock<Option<String>>>,
+    refresher: Arc<RwLock<Option<SessionRefresher>>>,
+}
+
+impl SupabaseClient {
+    pub fn new(config: SupabaseConfig) -> Self {
+        Self {
+            config,
+            retry: RetryPolicy::default(),
+            cache: RequestCache::default(),
+            access_token: Arc::new(RwLock::new(None)),
+            refresher: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn realtime_endpoint(&self) -> String {
+        self.config.realtime_endpoint()
+    }
+
+    /// Set (or clear) the user access token sent as the Bearer token.
+    /// Cached responses are dropped when it changes, since row-level security
+    /// may give the new user different rows.
+    pub fn set_access_token(&self, token: Option<String>) {
+        if let Ok(mut current) = self.access_token.write() {
+            if *current != token {
+                self.cache.clear();
+            }
+            *current = token;
+        }
+    }
+
+    /// Register how to refresh the session when a request comes back 401.
+    pub fn on_unauthorized(&self, refresher: SessionRefresher) {
+        if let Ok(mut current) = self.refresher.write() {
+            *current = Some(refresher);
+        }
+    }
+
+    /// Refresh the session once after `url` was rejected with 401.
+    /// Auth endpoints are left out, so a failing refresh never triggers another one.
+    async fn refresh_after_unauthorized(&self, url: &str) -> bool {
+        if self.access_token().is_none() || url.starts_with(&self.auth_endpoint("/auth/v1/")) {
+            return false;
+        }
+        let refresher = self.refresher.read().ok().and_then(|r| r.clone());
+        match refresher {
+            Some(refresh) => refresh().await,
+            None => false,
+        }
+    }
+
+    /// Drop cached responses read from `table`, after it has been written to.
+    pub fn invalidate_table(&self, table: &str) {
+        self.cache.invalidate(table);
+    }
+
+    pub fn access_token(&self) -> Option<String> {
+        self.access_token.read().ok().and_then(|t| t.clone())
+    }
+
+    /// Full URL for a path relative to the project root, e.g. `/auth/v1/user`.
+    pub fn endpoint(&self, path: &str) -> String {
+        format!("{}{}", self.config.url, path)
+    }
+
+    /// Full URL for an auth path such as `/auth/v1/token`, see `SupabaseConfig::auth_endpoint`.
+    pub fn auth_endpoint(&self, path: &str) -> String {
+        self.config.auth_endpoint(path)
+    }
+
+    /// Full URL for a PostgREST path relative to `/rest/v1/`, e.g. `areas?select=*`.
+    pub fn rest_url(&self, path: &str) -> String {
+        format!("{}/rest/v1/{}", self.config.url, path.trim_start_matches('/'))
+    }
+
+    /// Bearer token for the Authorization header: the user token when signed in,
+    /// otherwise the anon key if it is a JWT. New-style API keys send no Bearer.
+    fn bearer_token(&self) -> Option<String> {
+        self.access_token().or_else(|| {
+            if self.config.needs_auth_header() {
+                Some(self.config.api_key.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Request builder with the `apikey` and Authorization headers applied.
+    pub fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let mut request = RequestBuilder::new(url)
+            .method(method)
+            .header("apikey", &self.config.api_key);
+
+        if let Some(token) = self.bearer_token() {
+            request = request.header("Authorization", &format!("Bearer {}", token));
+        }
+
+        request
+    }
+
+    /// Send a request under the retry policy and read the response to the end.
+    /// A 401 refreshes the session and sends the request once more with the new token.
+    ///
+    /// Each attempt gets its own `AbortController`: it is aborted when the timeout fires,
+    /// and when the returned future is dropped, so cancelling a task cancels the fetch too.
+    pub async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> Result<RawResponse, SupabaseError> {
+        let result = self.send_with_retry(method.clone(), url, headers, body).await;
+        if matches!(&result, Ok(resp) if resp.status == 401) && self.refresh_after_unauthorized(url).await {
+            return self.send_with_retry(method, url, headers, body).await;
+        }
+        result
+    }
+
+    async fn send_with_retry(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> Result<RawResponse, SupabaseError> {
+        let mut attempt = 1;
+        loop {
+            let result = self.attempt(method.clone(), url, headers, body).await;
+            let retry = match &result {
+                Ok(resp) => self.retry.retries_status(&method, resp.status),
+                Err(err) => self.retry.retries_error(&method, err),
+            };
+            if !retry || attempt >= self.retry.max_attempts {
+                return result;
+            }
+            TimeoutFuture::new(self.retry.backoff_ms(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send a binary body, such as a file upload, in a single attempt under the longer
+    /// upload timeout. Uploads are not retried; only a 401 sends them again, after
+    /// refreshing the session.
+    pub async fn send_blob(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: &Blob,
+    ) -> Result<RawResponse, SupabaseError> {
+        let result = self.send_blob_once(method.clone(), url, headers, body).await;
+        if matches!(&result, Ok(resp) if resp.status == 401) && self.refresh_after_unauthorized(url).await {
+            return self.send_blob_once(method, url, headers, body).await;
+        }
+        result
+    }
+
+    async fn send_blob_once(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: &Blob,
+    ) -> Result<RawResponse, SupabaseError> {
+        let abort = AbortOnDrop::new()?;
+        let mut request = self.request(method, url).abort_signal(Some(&abort.signal()));
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let request = request.body(body.clone())
+            .map_err(|e| SupabaseError::Request(e.to_string()))?;
+
+        exchange(request, self.retry.upload_timeout_ms).await
+    }
+
+    async fn attempt(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> Result<RawResponse, SupabaseError> {
+        let abort = AbortOnDrop::new()?;
+        let mut request = self.request(method, url).abort_signal(Some(&abort.signal()));
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let request = match body {
+            Some(body) => request.body(body.to_string()),
+            None => request.build(),
+        }.map_err(|e| SupabaseError::Request(e.to_string()))?;
+
+        exchange(request, self.retry.timeout_ms).await
+    }
+
+    async fn send_json<U>(&self, method: Method, path: &str, payload: &U) -> Result<RawResponse, SupabaseError>
+    where
+        U: Serialize,
+    {
+        let body = serde_json::to_string(payload)
+            .map_err(|e| SupabaseError::Request(e.to_string()))?;
+
+        self.send(method, &self.rest_url(path), &[
+            ("Content-Type", "application/json"),
+            ("Prefer", "return=representation"),
+            ("Accept", "application/vnd.pgrst.object+json"),
+        ], Some(&body)).await
+    }
+
+    /// GET through the request cache: fresh responses are served as is, stale ones are
+    /// served while a background request refreshes them, and identical requests
+    /// already in flight are joined rather than sent again.
+    async fn cached_get(&self, path: &str, headers: Vec<(&'static str, String)>) -> Result<RawResponse, SupabaseError> {
+        let range = headers.iter().find(|(name, _)| *name == "Range").map(|(_, value)| value.as_str());
+        let key = format!("{} {}", path, range.unwrap_or_default());
+
+        match self.cache.lookup(&key, Date::now()) {
+            Lookup::Fresh(resp) => Ok(resp),
+            Lookup::Stale(resp) => {
+                let client = self.clone();
+                let path = path.to_string();
+                spawn_local(async move {
+                    let _ = client.shared_get(&key, &path, &headers).await;
+                });
+                Ok(resp)
+            }
+            Lookup::Miss => self.shared_get(&key, path, &headers).await,
+        }
+    }
+
+    async fn shared_get(&self, key: &str, path: &str, headers: &[(&'static str, String)]) -> Result<RawResponse, SupabaseError> {
+        loop {
+            match self.cache.join(key) {
+                Flight::Follower(result) => match result.await {
+                    Ok(result) => return result,
+                    // The request we joined was cancelled; send our own
+                    Err(_) => continue,
+                },
+                Flight::Leader(flight) => {
+                    let headers: Vec<(&str, &str)> = headers.iter().map(|(name, value)| (*name, value.as_str())).collect();
+                    let result = self.send(Method::GET, &self.rest_url(path), &headers, None).await;
+                    if let Ok(resp) = &result {
+                        if resp.ok() {
+                            self.cache.store(key, table_of(path), resp.clone(), Date::now());
+                        }
+                    }
+                    flight.finish(&result);
+                    return result;
+                }
+            }
+        }
+    }
+
+    pub async fn get<T>(&self, path: &str) -> Result<T, SupabaseError>
+    where
+        T: DeserializeOwned,
+    {
+        let resp = self.cached_get(path, vec![]).await?;
+
+        if resp.status == 200 {
+            resp.json()
+        } else {
+            Err(resp.error(path))
+        }
+    }
+
+    /// Fetch one page of a query. The query's range is sent as a `Range` header with
+    /// `Prefer: count=exact`, and the total is read back from `Content-Range`.
+    pub async fn get_page<T>(&self, query: &Query) -> Result<Page<T>, SupabaseError>
+    where
+        T: DeserializeOwned,
+    {
+        let path = query.without_range().to_string();
+        let (offset, limit, range) = match query.get_range() {
+            Some((from, to)) => (from, to - from + 1, Some(format!("{}-{}", from, to))),
+            None => (0, usize::MAX, None),
+        };
+        let mut headers = vec![("Prefer", "count=exact".to_string())];
+        if let Some(range) = range {
+            headers.push(("Range-Unit", "items".to_string()));
+            headers.push(("Range", range));
+        }
+
+        let resp = self.cached_get(&path, headers).await?;
+        let total = resp.content_range.as_deref().and_then(content_range_total);
+
+        match resp.status {
+            200 | 206 => Ok(Page {
+                items: resp.json()?,
+                offset,
+                limit,
+                total,
+            }),
+            // Asking past the end of the result is not an error, just an empty page
+            416 => Ok(Page { items: vec![], offset, limit, total }),
+            _ => Err(resp.error(&path)),
+        }
+    }
+
+    pub async fn insert<T, U>(&self, path: &str, payload: &U) -> Result<T, SupabaseError>
+    where
+        T: DeserializeOwned,
+        U: Serialize,
+    {
+        let resp = self.send_json(Method::POST, path, payload).await?;
+
+        if resp.status == 201 || resp.status == 200 {
+            resp.json()
+        } else {
+            Err(resp.error(path))
+        }
+    }
+
+    pub async fn update<T, U>(&self, path: &str, payload: &U) -> Result<T, SupabaseError>
+    where
+        T: DeserializeOwned,
+        U: Serialize,
+    {
+        let resp = self.send_json(Method::PATCH, path, payload).await?;
+
+        if resp.status == 200 {
+            resp.json()
+        } else {
+            Err(resp.error(path))
+        }
+    }
+
+    /// Insert many rows in one request, updating the rows whose primary key already exists.
+    /// Only the columns present in `rows` are written.
+    pub async fn upsert<T, U>(&self, table: &str, rows: &[U]) -> Result<Vec<T>, SupabaseError>
+    where
+        T: DeserializeOwned,
+        U: Serialize,
+    {
+        self.send_rows(table, rows, "resolution=merge-duplicates,return=representation").await
+    }
+
+    async fn send_rows<T, U>(&self, table: &str, rows: &[U], prefer: &str) -> Result<Vec<T>, SupabaseError>
+    where
+        T: DeserializeOwned,
+        U: Serialize,
+    {
+        if rows.is_empty() {
+            return Ok(vec![]);
+        }
+        let body = serde_json::to_string(rows)
+            .map_err(|e| SupabaseError::Request(e.to_string()))?;
+
+        let resp = self.send(Method::POST, &self.rest_url(table), &[
+            ("Content-Type", "application/json"),
+            ("Prefer", prefer),
+        ], Some(&body)).await?;
+
+        if resp.status == 201 || resp.status == 200 {
+            resp.json()
+        } else {
+            Err(resp.error(table))
+        }
+    }
+
+    /// Delete every row matching the query's filters in one request.
+    /// A query without filters is refused rather than emptying the table.
+    pub async fn delete_where(&self, query: &Query) -> Result<(), SupabaseError> {
+        if !query.has_filters() {
+            return Err(SupabaseError::Request(format!("Refusing to delete from {} without a filter", query.table_name())));
+        }
+        self.delete(&query.to_string()).await
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), SupabaseError> {
+        let resp = self.send(Method::DELETE, &self.rest_url(path), &[], None).await?;
+
+        if resp.ok() {
+            Ok(())
+        } else {
+            Err(resp.error(path))
+        }
+    }
+
+    /// Call a Postgres function exposed at `/rest/v1/rpc/<function>`.
+    pub async fn rpc<T, U>(&self, function: &str, args: &U) -> Result<T, SupabaseError>
+    where
+        T: DeserializeOwned,
+        U: Serialize,
+    {
+        let path = format!("rpc/{}", function);
+        let body = serde_json::to_string(args)
+            .map_err(|e| SupabaseError::Request(e.to_string()))?;
+
+        let resp = self.send(
+            Method::POST,
+            &self.rest_url(&path),
+            &[("Content-Type", "application/json")],
+            Some(&body),
+        ).await?;
+
+        if resp.status == 200 {
+            resp.json()
+        } else {
+            Err(resp.error(&path))
+        }
+    }
+}
+
+/// Table a PostgREST path reads from: `areas_view?select=*` -> `areas_view`.
+fn table_of(path: &str) -> &str {
+    path.split('?').next().unwrap_or_default().trim_start_matches('/')
+}
+
+/// A response whose body has been read, so it can be inspected after the request is finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawResponse {
+    pub status: u16,
+    pub content_range: Option<String>,
+    pub text: String,
+}
+
+impl RawResponse {
+    async fn read(resp: Response) -> Result<Self, SupabaseError> {
+        Ok(Self {
+            status: resp.status(),
+            content_range: resp.headers().get("content-range"),
+            text: resp.text().await.map_err(|e| SupabaseError::Network(e.to_string()))?,
+        })
+    }
+
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn json<T>(&self) -> Result<T, SupabaseError>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_str(&self.text).map_err(|e| SupabaseError::Deserialize(e.to_string()))
+    }
+
+    /// The error for a non-success response to `path`.
+    pub fn error(&self, path: &str) -> SupabaseError {
+        SupabaseError::from_status(self.status, path, &self.text)
+    }
+}
+
+/// Send a request and read its response, giving up after `timeout_ms`. The caller's
+/// [`AbortOnDrop`] then cancels the request in the browser.
+async fn exchange(request: Request, timeout_ms: u32) -> Result<RawResponse, SupabaseError> {
+    let exchange = async {
+        let resp = request.send().await.map_err(|e| SupabaseError::Network(e.to_string()))?;
+        RawResponse::read(resp).await
+    };
+    let timeout = TimeoutFuture::new(timeout_ms);
+    pin_mut!(exchange);
+
+    match select(exchange, timeout).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(SupabaseError::Timeout),
+    }
+}
+
+/// `AbortController` that aborts its request when dropped.
+struct AbortOnDrop(AbortController);
+
+impl AbortOnDrop {
+    fn new() -> Result<Self, SupabaseError> {
+        AbortController::new()
+            .map(Self)
+            .map_err(|_| SupabaseError::Request("AbortController is not available".to_string()))
+    }
+
+    fn signal(&self) -> AbortSignal {
+        self.0.signal()
+    }
+}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        // A no-op once the response has been read
+        self.0.abort();
+    }
+}
+
+#[component]
+pub fn SupabaseProvider(
+    config: SupabaseConfig,
+    children: Children,
+) -> impl IntoView {
+    let client = SupabaseClient::new(config);
+    provide_context(client);
+    children()
+}
+
+pub fn use_supabase() -> SupabaseClient {
+    use_context::<SupabaseClient>().expect(
+        "SupabaseClient not found. Make sure you are using SupabaseProvider."
+    )
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
+
+/// Error body returned by PostgREST (`code`, `message`, `details`, `hint`)
+/// or GoTrue (`error_code`, `msg`, `error`, `error_description`).
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ApiError {
+    #[serde(default, deserialize_with = "code_as_string")]
+    pub code: Option<String>,
+    pub error_code: Option<String>,
+    pub message: Option<String>,
+    pub msg: Option<String>,
+    pub details: Option<String>,
+    pub hint: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+// PostgREST sends the SQLSTATE as a string ("23505"), GoTrue sends the HTTP status as a number.
+fn code_as_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(code)) => Some(code),
+        Some(serde_json::Value::Number(code)) => Some(code.to_string()),
+        _ => None,
+    })
+}
+
+impl ApiError {
+    /// The most specific human-readable message in the payload.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+            .or(self.msg.as_deref())
+            .or(self.error_description.as_deref())
+            .or(self.error.as_deref())
+    }
+
+    /// Column named in a constraint violation detail such as `Key (title)=(Foo) already exists.`
+    fn key_column(&self) -> Option<&str> {
+        let details = self.details.as_deref()?;
+        let start = details.find("Key (")? + "Key (".len();
+        let end = details[start..].find(')')? + start;
+        Some(&details[start..end])
+    }
+
+    fn is_jwt_expired(&self) -> bool {
+        let code = self.code.as_deref().unwrap_or_default();
+        code.starts_with("PGRST30")
+            || self.error_code.as_deref() == Some("bad_jwt")
+            || self.message().is_some_and(|m| m.contains("JWT expired"))
+    }
+
+    /// Describe the error in terms of the table (or endpoint) it happened on.
+    pub fn describe(&self, resource: &str) -> String {
+        match self.code.as_deref() {
+            Some("23505") => match self.key_column() {
+                Some(column) => format!("duplicate key on {}.{}", resource, column),
+                None => format!("duplicate key on {}", resource),
+            },
+            Some("23503") => format!(
+                "{} is referenced by other records{}",
+                resource,
+                self.details.as_ref().map(|d| format!(": {}", d)).unwrap_or_default()
+            ),
+            Some("42501") => format!("permission denied on {}: your role does not allow this change", resource),
+            _ => {
+                let message = self.message().unwrap_or("request failed").to_string();
+                match &self.hint {
+                    Some(hint) => format!("{} ({})", message, hint),
+                    None => message,
+                }
+            }
+        }
+    }
+}
+
+/// Failure of a request to the Supabase REST, auth or RPC endpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SupabaseError {
+    /// The request never reached the server or the connection dropped.
+    Network(String),
+    /// No response arrived within the retry policy's timeout.
+    Timeout,
+    /// The server answered with a non-success status.
+    Http {
+        status: u16,
+        resource: String,
+        body: Option<Box<ApiError>>,
+    },
+    /// The response body did not match the expected type.
+    Deserialize(String),
+    /// The access token was rejected as expired.
+    AuthExpired,
+    /// The request could not be built, e.g. a payload failed to serialize.
+    Request(String),
+}
+
+impl SupabaseError {
+    /// Build an error from a non-success response, keeping the parsed error body.
+    pub fn from_status(status: u16, path: &str, text: &str) -> Self {
+        let body = serde_json::from_str::<ApiError>(text).ok();
+        if status == 401 && body.as_ref().is_some_and(ApiError::is_jwt_expired) {
+            return SupabaseError::AuthExpired;
+        }
+        SupabaseError::Http {
+            status,
+            resource: resource_name(path),
+            body: body.map(Box::new),
+        }
+    }
+
+    /// The server could not be reached, so the request may succeed later as is.
+    pub fn is_offline(&self) -> bool {
+        matches!(self, SupabaseError::Network(_) | SupabaseError::Timeout)
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            SupabaseError::Http { status, .. } => Some(*status),
+            SupabaseError::AuthExpired => Some(401),
+            _ => None,
+        }
+    }
+}
+
+/// Table or endpoint name from a request path: `/rest/v1/areas?id=eq.1` -> `areas`.
+/// Storage paths name their bucket: `/storage/v1/object/project-images/a.png` -> `project-images`.
+fn resource_name(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default().trim_start_matches('/');
+    match path.strip_prefix("storage/v1/object/") {
+        Some(object) => {
+            let object = ["public/", "sign/", "list/", "authenticated/"]
+                .iter()
+                .find_map(|prefix| object.strip_prefix(prefix))
+                .unwrap_or(object);
+            object.split('/').next().unwrap_or_default().to_string()
+        }
+        None => path.trim_start_matches("rest/v1/").to_string(),
+    }
+}
+
+impl fmt::Display for SupabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SupabaseError::Network(err) => write!(f, "Network error: {}", err),
+            SupabaseError::Timeout => write!(f, "The server took too long to respond. Please try again."),
+            SupabaseError::Http { status, resource, body: Some(body) } => {
+                write!(f, "{} (HTTP {})", body.describe(resource), status)
+            }
+            SupabaseError::Http { status: 403, resource, body: None } => {
+                write!(f, "permission denied on {}: your role does not allow this change (HTTP 403)", resource)
+            }
+            SupabaseError::Http { status, resource, body: None } => {
+                write!(f, "{} failed: HTTP {}", resource, status)
+            }
+            SupabaseError::Deserialize(err) => write!(f, "Unexpected response: {}", err),
+            SupabaseError::AuthExpired => write!(f, "Your session has expired. Please sign in again."),
+            SupabaseError::Request(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SupabaseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permission_errors_name_the_resource() {
+        let err = SupabaseError::from_status(
+            403,
+            "/rest/v1/projects?id=eq.1",
+            r#"{"code":"42501","message":"new row violates row-level security policy for table \"projects\""}"#,
+        );
+        assert_eq!(err.to_string(), "permission denied on projects: your role does not allow this change (HTTP 403)");
+        let err = SupabaseError::from_status(403, "/storage/v1/object/project-images/a.png", "");
+        assert!(err.to_string().starts_with("permission denied on project-images:"));
+    }
+
+    #[test]
+    fn parses_postgrest_and_gotrue_bodies() {
+        let cases = [
+            (
+                r#"{"code":"23505","details":"Key (title)=(Welding) already exists.","hint":null,"message":"duplicate key value violates unique constraint \"areas_title_key\""}"#,
+                Some("23505"),
+                Some("duplicate key value violates unique constraint \"areas_title_key\""),
+            ),
+            (
+                r#"{"code":400,"error_code":"validation_failed","msg":"Unable to validate email address: invalid format"}"#,
+                Some("400"),
+                Some("Unable to validate email address: invalid format"),
+            ),
+            (
+                r#"{"error":"invalid_grant","error_description":"Invalid login credentials"}"#,
+                None,
+                Some("Invalid login credentials"),
+            ),
+        ];
+        for (body, code, message) in cases {
+            let error: ApiError = serde_json::from_str(body).unwrap();
+            assert_eq!(error.code.as_deref(), code, "{}", body);
+            assert_eq!(error.message(), message, "{}", body);
+        }
+    }
+
+    #[test]
+    fn describes_errors_in_terms_of_the_resource() {
+        let cases = [
+            (
+                409,
+                "/rest/v1/areas",
+                r#"{"code":"23505","details":"Key (title)=(Welding) already exists.","hint":null,"message":"duplicate key value violates unique constraint \"areas_title_key\""}"#,
+                "duplicate key on areas.title (HTTP 409)",
+            ),
+            (
+                409,
+                "/rest/v1/areas",
+                r#"{"code":"23505","details":null,"hint":null,"message":"duplicate key value violates unique constraint \"areas_pkey\""}"#,
+                "duplicate key on areas (HTTP 409)",
+            ),
+            (
+                409,
+                "/rest/v1/projects?id=eq.1",
+                r#"{"code":"23503","details":"Key is still referenced from table \"catalog\".","hint":null,"message":"update or delete on table \"projects\" violates foreign key constraint \"catalog_project_id_fkey\" on table \"catalog\""}"#,
+                "projects is referenced by other records: Key is still referenced from table \"catalog\". (HTTP 409)",
+            ),
+            (
+                400,
+                "/rest/v1/rpc/set_project_areas",
+                r#"{"code":"PGRST202","details":null,"hint":"Perhaps you meant to call the function public.set_project_areas","message":"Could not find the function public.set_project_areas(p_ids) in the schema cache"}"#,
+                "Could not find the function public.set_project_areas(p_ids) in the schema cache (Perhaps you meant to call the function public.set_project_areas) (HTTP 400)",
+            ),
+            (404, "/storage/v1/object/public/avatars/u1/a.png", "", "avatars failed: HTTP 404"),
+        ];
+        for (status, path, body, expected) in cases {
+            assert_eq!(SupabaseError::from_status(status, path, body).to_string(), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn only_an_expired_token_is_auth_expired() {
+        let cases = [
+            (r#"{"code":"PGRST301","details":null,"hint":null,"message":"JWT expired"}"#, true),
+            (r#"{"code":401,"error_code":"bad_jwt","msg":"invalid JWT: unable to parse or verify signature, token has invalid claims: token is expired"}"#, true),
+            (r#"{"message":"No API key found in request","hint":"No `apikey` request header or url param was found."}"#, false),
+            ("", false),
+        ];
+        for (body, expired) in cases {
+            let err = SupabaseError::from_status(401, "/rest/v1/projects", body);
+            assert_eq!(err == SupabaseError::AuthExpired, expired, "{}", body);
+            assert_eq!(err.status(), Some(401));
+        }
+        let err = SupabaseError::from_status(403, "/rest/v1/projects", r#"{"code":"PGRST301","message":"JWT expired"}"#);
+        assert_ne!(err, SupabaseError::AuthExpired);
+    }
+
+    #[test]
+    fn resource_names_drop_the_api_prefix() {
+        let cases = [
+            ("/rest/v1/areas?id=eq.1", "areas"),
+            ("rest/v1/projects", "projects"),
+            ("/rest/v1/rpc/set_project_areas", "rpc/set_project_areas"),
+            ("/storage/v1/object/project-images/projects/1/a.png", "project-images"),
+            ("/storage/v1/object/public/avatars/u1/a.png", "avatars"),
+            ("/storage/v1/object/list/project-images", "project-images"),
+            ("/auth/v1/token?grant_type=password", "auth/v1/token"),
+        ];
+        for (path, resource) in cases {
+            assert_eq!(resource_name(path), resource, "{}", path);
+        }
+    }
+}
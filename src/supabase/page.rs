@@ -0,0 +1,96 @@
+/// One slice of a list fetched with a `Range` header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Index of the first item in the full result.
+    pub offset: usize,
+    /// Number of rows that were asked for.
+    pub limit: usize,
+    /// Row count of the full result, from `Content-Range` with `Prefer: count=exact`.
+    pub total: Option<usize>,
+}
+
+impl<T> Page<T> {
+    /// Slice `offset..offset + limit` of an in-memory list.
+    pub fn slice(all: &[T], offset: usize, limit: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            items: all.iter().skip(offset).take(limit).cloned().collect(),
+            offset,
+            limit,
+            total: Some(all.len()),
+        }
+    }
+
+    pub fn next_offset(&self) -> usize {
+        self.offset + self.items.len()
+    }
+
+    /// Whether rows remain after this page. Without a total, a full page means there may be more.
+    pub fn has_more(&self) -> bool {
+        match self.total {
+            Some(total) => self.next_offset() < total,
+            None => !self.items.is_empty() && self.items.len() >= self.limit,
+        }
+    }
+
+    /// Insert this page's items into `items`, replacing those with the same key.
+    pub fn merge_into<K, F>(&self, items: &mut Vec<T>, key: F)
+    where
+        T: Clone,
+        K: PartialEq,
+        F: Fn(&T) -> K,
+    {
+        for item in &self.items {
+            match items.iter_mut().find(|existing| key(existing) == key(item)) {
+                Some(existing) => *existing = item.clone(),
+                None => items.push(item.clone()),
+            }
+        }
+    }
+}
+
+/// Total from a `Content-Range` header such as `0-24/3573`, `*/0` or `0-24/*`.
+pub fn content_range_total(header: &str) -> Option<usize> {
+    header.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_content_range_totals() {
+        assert_eq!(content_range_total("0-24/3573"), Some(3573));
+        assert_eq!(content_range_total("*/0"), Some(0));
+        assert_eq!(content_range_total("0-24/*"), None);
+        assert_eq!(content_range_total("garbage"), None);
+    }
+
+    #[test]
+    fn has_more_uses_total_when_known() {
+        let page = Page::slice(&[1, 2, 3, 4, 5], 2, 2);
+        assert_eq!(page.items, vec![3, 4]);
+        assert_eq!(page.next_offset(), 4);
+        assert!(page.has_more());
+        assert!(!Page::slice(&[1, 2, 3, 4, 5], 4, 2).has_more());
+    }
+
+    #[test]
+    fn has_more_without_total_checks_for_a_full_page() {
+        let full = Page { items: vec![1, 2], offset: 0, limit: 2, total: None };
+        let short = Page { items: vec![3], offset: 2, limit: 2, total: None };
+        assert!(full.has_more());
+        assert!(!short.has_more());
+    }
+
+    #[test]
+    fn merge_replaces_items_with_the_same_key() {
+        let mut items = vec![(1, "a"), (2, "b")];
+        let page = Page { items: vec![(2, "B"), (3, "c")], offset: 1, limit: 2, total: Some(3) };
+        page.merge_into(&mut items, |item| item.0);
+        assert_eq!(items, vec![(1, "a"), (2, "B"), (3, "c")]);
+    }
+}
@@ -0,0 +1,214 @@
+use std::fmt;
+
+/// PostgREST request path for a table or view, relative to `/rest/v1/`.
+///
+/// ```ignore
+/// let path = Query::table("catalog")
+///     .select("*")
+///     .eq("project_id", 3)
+///     .in_("area_id", [1, 2])
+///     .to_string(); // catalog?select=*&project_id=eq.3&area_id=in.(1,2)
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    table: String,
+    select: Option<String>,
+    filters: Vec<(String, String)>,
+    order: Vec<String>,
+    range: Option<(usize, usize)>,
+}
+
+impl Query {
+    pub fn table(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            select: None,
+            filters: vec![],
+            order: vec![],
+            range: None,
+        }
+    }
+
+    /// Columns to return, including embedded resources such as `*,catalog(area_id)`.
+    pub fn select(mut self, columns: &str) -> Self {
+        let columns: String = columns.chars().filter(|c| !c.is_whitespace()).collect();
+        self.select = Some(columns);
+        self
+    }
+
+    fn filter(mut self, column: &str, operator: &str, value: impl fmt::Display) -> Self {
+        self.filters.push((
+            column.to_string(),
+            format!("{}.{}", operator, encode(&value.to_string())),
+        ));
+        self
+    }
+
+    pub fn eq(self, column: &str, value: impl fmt::Display) -> Self {
+        self.filter(column, "eq", value)
+    }
+
+    pub fn in_<I, V>(mut self, column: &str, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: fmt::Display,
+    {
+        let values = values
+            .into_iter()
+            .map(|v| encode(&quote_list_item(&v.to_string())))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.filters.push((column.to_string(), format!("in.({})", values)));
+        self
+    }
+
+    /// Sort ascending by `column`, after any columns ordered before it.
+    pub fn order_asc(mut self, column: &str) -> Self {
+        self.order.push(column.to_string());
+        self
+    }
+
+    /// Rows `from..=to` (zero-based, inclusive), like the `Range` header.
+    pub fn range(mut self, from: usize, to: usize) -> Self {
+        self.range = Some((from, to.max(from)));
+        self
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table
+    }
+
+    pub fn has_filters(&self) -> bool {
+        !self.filters.is_empty()
+    }
+
+    pub fn get_range(&self) -> Option<(usize, usize)> {
+        self.range
+    }
+
+    /// The same query without `offset`/`limit`, for sending the range as a `Range` header instead.
+    pub fn without_range(&self) -> Self {
+        Self {
+            range: None,
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut params: Vec<String> = vec![];
+
+        if let Some(select) = &self.select {
+            params.push(format!("select={}", select));
+        }
+        for (column, condition) in &self.filters {
+            params.push(format!("{}={}", encode(column), condition));
+        }
+        if !self.order.is_empty() {
+            let order = self.order
+                .iter()
+                .map(|column| format!("{}.asc", encode(column)))
+                .collect::<Vec<_>>()
+                .join(",");
+            params.push(format!("order={}", order));
+        }
+        if let Some((from, to)) = self.range {
+            params.push(format!("offset={}", from));
+            params.push(format!("limit={}", to - from + 1));
+        }
+
+        if params.is_empty() {
+            write!(f, "{}", self.table)
+        } else {
+            write!(f, "{}?{}", self.table, params.join("&"))
+        }
+    }
+}
+
+/// Values inside `in.(...)` must be double-quoted when they contain PostgREST delimiters.
+fn quote_list_item(value: &str) -> String {
+    if value.contains([',', '(', ')', '"', '\\', ' ', '.', ':']) {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_table() {
+        assert_eq!(Query::table("projects").to_string(), "projects");
+    }
+
+    #[test]
+    fn select_and_eq_filters() {
+        let query = Query::table("catalog")
+            .select("*")
+            .eq("project_id", 3)
+            .eq("area_id", 7);
+        assert_eq!(query.to_string(), "catalog?select=*&project_id=eq.3&area_id=eq.7");
+    }
+
+    #[test]
+    fn embedded_resource_select() {
+        let query = Query::table("projects").select("*, catalog(area_id)");
+        assert_eq!(query.to_string(), "projects?select=*,catalog(area_id)");
+    }
+
+    #[test]
+    fn filter_values_are_url_encoded() {
+        let query = Query::table("areas").eq("title", "R&D / 10% ?");
+        assert_eq!(query.to_string(), "areas?title=eq.R%26D%20%2F%2010%25%20%3F");
+    }
+
+    #[test]
+    fn in_list_quotes_reserved_values() {
+        let query = Query::table("areas").in_("category", ["Scale", "a,b", "x\"y"]);
+        assert_eq!(
+            query.to_string(),
+            "areas?category=in.(Scale,%22a%2Cb%22,%22x%5C%22y%22)"
+        );
+    }
+
+    #[test]
+    fn in_list_of_ids() {
+        let query = Query::table("areas").in_("id", vec![1_i64, 2, 3]);
+        assert_eq!(query.to_string(), "areas?id=in.(1,2,3)");
+    }
+
+    #[test]
+    fn order_and_range() {
+        let query = Query::table("projects")
+            .select("*")
+            .order_asc("order")
+            .order_asc("created_at")
+            .range(20, 29);
+        assert_eq!(
+            query.to_string(),
+            "projects?select=*&order=order.asc,created_at.asc&offset=20&limit=10"
+        );
+        assert_eq!(query.get_range(), Some((20, 29)));
+        assert_eq!(
+            query.without_range().to_string(),
+            "projects?select=*&order=order.asc,created_at.asc"
+        );
+    }
+}
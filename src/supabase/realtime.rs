@@ -0,0 +1,472 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use gloo_timers::callback::{Interval, Timeout};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{Event, MessageEvent, WebSocket};
+
+const HEARTBEAT_MS: u32 = 30_000;
+const MAX_RECONNECT_DELAY_MS: u32 = 30_000;
+
+/// A frame of the Phoenix channel protocol spoken by Supabase Realtime.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhoenixMessage {
+    pub topic: String,
+    pub event: String,
+    pub payload: Value,
+    #[serde(rename = "ref", default)]
+    pub msg_ref: Option<String>,
+    #[serde(default)]
+    pub join_ref: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChangeKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A row change on a subscribed table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Change {
+    pub table: String,
+    #[serde(rename = "type")]
+    pub kind: ChangeKind,
+    /// The row after an insert or update.
+    #[serde(default)]
+    pub record: Value,
+    /// The primary key of a deleted or updated row (the whole row with `REPLICA IDENTITY FULL`).
+    #[serde(default)]
+    pub old_record: Value,
+}
+
+impl Change {
+    pub fn record<T: DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_value(self.record.clone()).ok()
+    }
+
+    pub fn old_id(&self) -> Option<i64> {
+        self.old_record.get("id")?.as_i64()
+    }
+
+    /// Apply the change to a list of rows identified by `id`: inserts and updates
+    /// replace or add the row, deletes remove it. Returns how the row count changed.
+    pub fn apply<T, F>(&self, items: &mut Vec<T>, id: F) -> i64
+    where
+        T: DeserializeOwned,
+        F: Fn(&T) -> i64,
+    {
+        let before = items.len() as i64;
+        match self.kind {
+            ChangeKind::Insert | ChangeKind::Update => {
+                if let Some(row) = self.record::<T>() {
+                    match items.iter_mut().find(|item| id(item) == id(&row)) {
+                        Some(existing) => *existing = row,
+                        None => items.push(row),
+                    }
+                }
+            }
+            ChangeKind::Delete => {
+                if let Some(old_id) = self.old_id() {
+                    items.retain(|item| id(item) != old_id);
+                }
+            }
+        }
+        items.len() as i64 - before
+    }
+}
+
+/// What to do with an incoming frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Incoming {
+    Change(Change),
+    /// Frame to send back, e.g. a rejoin after the server closed a channel.
+    Send(String),
+    Nothing,
+}
+
+/// State of the Realtime protocol without any I/O: builds the frames to send and
+/// interprets the frames received, so it can be driven by a browser socket or a test.
+#[derive(Debug, Clone)]
+pub struct RealtimeProtocol {
+    tables: Vec<String>,
+    access_token: Option<String>,
+    next_ref: u64,
+    /// Ref of the latest join per table, to tell its frames from those of a channel already left.
+    join_refs: HashMap<String, String>,
+}
+
+impl RealtimeProtocol {
+    pub fn new(tables: &[&str], access_token: Option<String>) -> Self {
+        Self {
+            tables: tables.iter().map(|t| t.to_string()).collect(),
+            access_token,
+            next_ref: 0,
+            join_refs: HashMap::new(),
+        }
+    }
+
+    fn topic(table: &str) -> String {
+        format!("realtime:public:{}", table)
+    }
+
+    fn next_ref(&mut self) -> String {
+        self.next_ref += 1;
+        self.next_ref.to_string()
+    }
+
+    fn frame(&mut self, topic: String, event: &str, payload: Value) -> String {
+        let msg_ref = self.next_ref();
+        let message = PhoenixMessage {
+            topic,
+            event: event.to_string(),
+            payload,
+            join_ref: Some(msg_ref.clone()),
+            msg_ref: Some(msg_ref),
+        };
+        serde_json::to_string(&message).unwrap_or_default()
+    }
+
+    fn join_frame(&mut self, table: &str) -> String {
+        let mut payload = json!({
+            "config": {
+                "postgres_changes": [{ "event": "*", "schema": "public", "table": table }],
+            },
+        });
+        if let Some(token) = &self.access_token {
+            payload["access_token"] = json!(token);
+        }
+        let frame = self.frame(Self::topic(table), "phx_join", payload);
+        self.join_refs.insert(table.to_string(), self.next_ref.to_string());
+        frame
+    }
+
+    /// Channel joins to send once the socket is open.
+    pub fn join_frames(&mut self) -> Vec<String> {
+        let tables = self.tables.clone();
+        tables.iter().map(|table| self.join_frame(table)).collect()
+    }
+
+    pub fn heartbeat_frame(&mut self) -> String {
+        self.frame("phoenix".to_string(), "heartbeat", json!({}))
+    }
+
+    /// Switch the token row-level security is evaluated with; returns frames for open channels.
+    /// Channels keep the last token they were given, so on sign-out they are left and
+    /// joined again without one, as the anonymous key.
+    pub fn set_access_token(&mut self, token: Option<String>) -> Vec<String> {
+        if self.access_token == token {
+            return vec![];
+        }
+        self.access_token = token;
+        let tables = self.tables.clone();
+        match self.access_token.clone() {
+            Some(token) => tables
+                .iter()
+                .map(|table| self.frame(Self::topic(table), "access_token", json!({ "access_token": token })))
+                .collect(),
+            None => tables
+                .iter()
+                .flat_map(|table| [self.frame(Self::topic(table), "phx_leave", json!({})), self.join_frame(table)])
+                .collect(),
+        }
+    }
+
+    pub fn handle(&mut self, text: &str) -> Incoming {
+        let Ok(message) = serde_json::from_str::<PhoenixMessage>(text) else {
+            return Incoming::Nothing;
+        };
+        match message.event.as_str() {
+            "postgres_changes" => serde_json::from_value::<Change>(message.payload["data"].clone())
+                .map(Incoming::Change)
+                .unwrap_or(Incoming::Nothing),
+            "phx_error" | "phx_close" => {
+                match self.tables.iter().find(|t| Self::topic(t) == message.topic).cloned() {
+                    // A channel replaced by a later join, e.g. after sign-out, stays closed
+                    Some(table) if message.join_ref.is_some() && message.join_ref.as_ref() != self.join_refs.get(&table) => {
+                        Incoming::Nothing
+                    }
+                    Some(table) => Incoming::Send(self.join_frame(&table)),
+                    None => Incoming::Nothing,
+                }
+            }
+            _ => Incoming::Nothing,
+        }
+    }
+}
+
+struct SocketState {
+    url: String,
+    protocol: RealtimeProtocol,
+    on_change: Rc<dyn Fn(Change)>,
+    socket: Option<WebSocket>,
+    heartbeat: Option<Interval>,
+    reconnect: Option<Timeout>,
+    attempts: u32,
+    closed: bool,
+    // Kept alive for as long as the socket uses them
+    on_open: Option<Closure<dyn FnMut(Event)>>,
+    on_message: Option<Closure<dyn FnMut(MessageEvent)>>,
+    on_close: Option<Closure<dyn FnMut(Event)>>,
+}
+
+/// Browser websocket running a [`RealtimeProtocol`]. Reconnects with backoff until dropped.
+pub struct RealtimeSocket {
+    state: Rc<RefCell<SocketState>>,
+}
+
+impl RealtimeSocket {
+    pub fn connect(url: String, protocol: RealtimeProtocol, on_change: impl Fn(Change) + 'static) -> Self {
+        let state = Rc::new(RefCell::new(SocketState {
+            url,
+            protocol,
+            on_change: Rc::new(on_change),
+            socket: None,
+            heartbeat: None,
+            reconnect: None,
+            attempts: 0,
+            closed: false,
+            on_open: None,
+            on_message: None,
+            on_close: None,
+        }));
+        open(&state);
+        Self { state }
+    }
+
+    pub fn set_access_token(&self, token: Option<String>) {
+        let mut state = self.state.borrow_mut();
+        let frames = state.protocol.set_access_token(token);
+        if let Some(socket) = &state.socket {
+            for frame in frames {
+                let _ = socket.send_with_str(&frame);
+            }
+        }
+    }
+}
+
+impl Drop for RealtimeSocket {
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.closed = true;
+        state.heartbeat = None;
+        state.reconnect = None;
+        if let Some(socket) = state.socket.take() {
+            // Detach the handlers first: they are freed together with this socket
+            socket.set_onopen(None);
+            socket.set_onmessage(None);
+            socket.set_onclose(None);
+            let _ = socket.close();
+        }
+    }
+}
+
+fn open(state: &Rc<RefCell<SocketState>>) {
+    let url = state.borrow().url.clone();
+    let socket = match WebSocket::new(&url) {
+        Ok(socket) => socket,
+        Err(_) => return schedule_reconnect(state),
+    };
+
+    let weak = Rc::downgrade(state);
+    let on_open = Closure::<dyn FnMut(Event)>::new(move |_| {
+        let Some(state) = weak.upgrade() else { return };
+        let mut inner = state.borrow_mut();
+        inner.attempts = 0;
+        let frames = inner.protocol.join_frames();
+        if let Some(socket) = &inner.socket {
+            for frame in frames {
+                let _ = socket.send_with_str(&frame);
+            }
+        }
+        let heartbeat_state = Rc::downgrade(&state);
+        inner.heartbeat = Some(Interval::new(HEARTBEAT_MS, move || {
+            let Some(state) = heartbeat_state.upgrade() else { return };
+            let mut inner = state.borrow_mut();
+            let frame = inner.protocol.heartbeat_frame();
+            if let Some(socket) = &inner.socket {
+                let _ = socket.send_with_str(&frame);
+            }
+        }));
+    });
+
+    let weak = Rc::downgrade(state);
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let Some(state) = weak.upgrade() else { return };
+        let Some(text) = event.data().as_string() else { return };
+        let incoming = state.borrow_mut().protocol.handle(&text);
+        match incoming {
+            Incoming::Change(change) => {
+                // Release the borrow first: the handler updates signals that may touch the socket
+                let on_change = state.borrow().on_change.clone();
+                on_change(change);
+            }
+            Incoming::Send(frame) => {
+                if let Some(socket) = &state.borrow().socket {
+                    let _ = socket.send_with_str(&frame);
+                }
+            }
+            Incoming::Nothing => {}
+        }
+    });
+
+    let weak = Rc::downgrade(state);
+    let on_close = Closure::<dyn FnMut(Event)>::new(move |_| {
+        let Some(state) = weak.upgrade() else { return };
+        let closed = {
+            let mut inner = state.borrow_mut();
+            inner.heartbeat = None;
+            inner.socket = None;
+            inner.closed
+        };
+        if !closed {
+            schedule_reconnect(&state);
+        }
+    });
+
+    socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    let mut inner = state.borrow_mut();
+    inner.socket = Some(socket);
+    inner.on_open = Some(on_open);
+    inner.on_message = Some(on_message);
+    inner.on_close = Some(on_close);
+}
+
+fn schedule_reconnect(state: &Rc<RefCell<SocketState>>) {
+    let weak: Weak<RefCell<SocketState>> = Rc::downgrade(state);
+    let mut inner = state.borrow_mut();
+    inner.attempts += 1;
+    let delay = 1_000_u32
+        .saturating_mul(2_u32.saturating_pow(inner.attempts - 1))
+        .min(MAX_RECONNECT_DELAY_MS);
+    inner.reconnect = Some(Timeout::new(delay, move || {
+        if let Some(state) = weak.upgrade() {
+            open(&state);
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for the Realtime server: reads what the client sends and produces its replies.
+    struct StandIn;
+
+    impl StandIn {
+        fn joined_topics(frames: &[String]) -> Vec<String> {
+            frames
+                .iter()
+                .map(|f| serde_json::from_str::<PhoenixMessage>(f).unwrap())
+                .filter(|m| m.event == "phx_join")
+                .map(|m| m.topic)
+                .collect()
+        }
+
+        fn change(table: &str, kind: &str, record: Value, old_record: Value) -> String {
+            json!({
+                "topic": format!("realtime:public:{}", table),
+                "event": "postgres_changes",
+                "payload": {
+                    "ids": [1],
+                    "data": {
+                        "schema": "public",
+                        "table": table,
+                        "type": kind,
+                        "commit_timestamp": "2025-01-01T00:00:00Z",
+                        "record": record,
+                        "old_record": old_record,
+                    }
+                },
+                "ref": null
+            })
+            .to_string()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct Row {
+        id: i64,
+        title: String,
+    }
+
+    #[test]
+    fn joins_one_channel_per_table_with_the_token() {
+        let mut protocol = RealtimeProtocol::new(&["projects", "areas"], Some("jwt".to_string()));
+        let frames = protocol.join_frames();
+
+        assert_eq!(StandIn::joined_topics(&frames), vec!["realtime:public:projects", "realtime:public:areas"]);
+        let join: PhoenixMessage = serde_json::from_str(&frames[0]).unwrap();
+        assert_eq!(join.payload["access_token"], "jwt");
+        assert_eq!(join.payload["config"]["postgres_changes"][0]["table"], "projects");
+        assert_ne!(frames[0], frames[1]);
+    }
+
+    #[test]
+    fn applies_inserts_updates_and_deletes() {
+        let mut protocol = RealtimeProtocol::new(&["projects"], None);
+        let mut rows = vec![Row { id: 1, title: "Welding".to_string() }];
+
+        let frames = [
+            StandIn::change("projects", "INSERT", json!({ "id": 2, "title": "Blades" }), json!({})),
+            StandIn::change("projects", "UPDATE", json!({ "id": 1, "title": "Welding QA" }), json!({ "id": 1 })),
+            StandIn::change("projects", "DELETE", json!({}), json!({ "id": 2 })),
+        ];
+        let deltas: Vec<i64> = frames
+            .iter()
+            .map(|frame| match protocol.handle(frame) {
+                Incoming::Change(change) => change.apply(&mut rows, |r| r.id),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+
+        assert_eq!(deltas, vec![1, 0, -1]);
+        assert_eq!(rows, vec![Row { id: 1, title: "Welding QA".to_string() }]);
+    }
+
+    #[test]
+    fn rejoins_a_channel_the_server_closed() {
+        let mut protocol = RealtimeProtocol::new(&["catalog"], None);
+        let error = json!({ "topic": "realtime:public:catalog", "event": "phx_error", "payload": {}, "ref": "1" });
+
+        match protocol.handle(&error.to_string()) {
+            Incoming::Send(frame) => assert_eq!(StandIn::joined_topics(&[frame]), vec!["realtime:public:catalog"]),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(protocol.handle("{\"topic\":\"phoenix\",\"event\":\"phx_reply\",\"payload\":{}}"), Incoming::Nothing);
+    }
+
+    #[test]
+    fn token_changes_are_sent_to_every_channel() {
+        let mut protocol = RealtimeProtocol::new(&["areas", "content"], None);
+        assert_eq!(protocol.set_access_token(Some("a".to_string())).len(), 2);
+        assert!(protocol.set_access_token(Some("a".to_string())).is_empty());
+    }
+
+    #[test]
+    fn signing_out_rejoins_every_channel_without_the_token() {
+        let mut protocol = RealtimeProtocol::new(&["areas", "content"], Some("jwt".to_string()));
+        let first_joins = protocol.join_frames();
+        let frames = protocol.set_access_token(None);
+
+        let messages: Vec<PhoenixMessage> = frames.iter().map(|frame| serde_json::from_str(frame).unwrap()).collect();
+        let events: Vec<&str> = messages.iter().map(|message| message.event.as_str()).collect();
+        assert_eq!(events, vec!["phx_leave", "phx_join", "phx_leave", "phx_join"]);
+        assert!(messages[1].payload.get("access_token").is_none());
+
+        // The old channel closing does not join it a second time
+        let old_join: PhoenixMessage = serde_json::from_str(&first_joins[0]).unwrap();
+        let close = json!({ "topic": "realtime:public:areas", "event": "phx_close", "payload": {}, "join_ref": old_join.join_ref });
+        assert_eq!(protocol.handle(&close.to_string()), Incoming::Nothing);
+        let close = json!({ "topic": "realtime:public:areas", "event": "phx_close", "payload": {}, "join_ref": messages[1].join_ref });
+        assert!(matches!(protocol.handle(&close.to_string()), Incoming::Send(_)));
+    }
+}
@@ -0,0 +1,85 @@
+use gloo_net::http::Method;
+
+use crate::supabase::SupabaseError;
+
+/// Timeout and retry settings applied to every request sent by [`crate::supabase::SupabaseClient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each further retry.
+    pub base_delay_ms: u32,
+    pub max_delay_ms: u32,
+    /// Each attempt is aborted after this long.
+    pub timeout_ms: u32,
+    /// Uploads are sent once and aborted after this long, since a large file takes a while.
+    pub upload_timeout_ms: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 300,
+            max_delay_ms: 5_000,
+            timeout_ms: 15_000,
+            upload_timeout_ms: 120_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (1 for the first retry).
+    pub fn backoff_ms(&self, attempt: u32) -> u32 {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay_ms.saturating_mul(factor).min(self.max_delay_ms)
+    }
+
+    /// Whether a response with `status` should be retried. 429 means the request was
+    /// refused before it ran, so any method may repeat it; 5xx only for idempotent methods.
+    pub fn retries_status(&self, method: &Method, status: u16) -> bool {
+        status == 429 || (is_idempotent(method) && (500..600).contains(&status))
+    }
+
+    /// Whether a failed attempt should be retried: network errors and timeouts of idempotent requests.
+    pub fn retries_error(&self, method: &Method, err: &SupabaseError) -> bool {
+        is_idempotent(method) && matches!(err, SupabaseError::Network(_) | SupabaseError::Timeout)
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff_ms(1), 300);
+        assert_eq!(policy.backoff_ms(2), 600);
+        assert_eq!(policy.backoff_ms(3), 1_200);
+        assert_eq!(policy.backoff_ms(10), 5_000);
+        assert_eq!(policy.backoff_ms(40), 5_000);
+    }
+
+    #[test]
+    fn only_idempotent_requests_retry_server_errors() {
+        let policy = RetryPolicy::default();
+        assert!(policy.retries_status(&Method::GET, 503));
+        assert!(!policy.retries_status(&Method::POST, 503));
+        assert!(policy.retries_status(&Method::POST, 429));
+        assert!(!policy.retries_status(&Method::GET, 404));
+    }
+
+    #[test]
+    fn only_idempotent_requests_retry_network_errors() {
+        let policy = RetryPolicy::default();
+        assert!(policy.retries_error(&Method::GET, &SupabaseError::Timeout));
+        assert!(policy.retries_error(&Method::GET, &SupabaseError::Network("reset".to_string())));
+        assert!(!policy.retries_error(&Method::PATCH, &SupabaseError::Timeout));
+        assert!(!policy.retries_error(&Method::GET, &SupabaseError::Deserialize("eof".to_string())));
+    }
+}
@@ -0,0 +1,186 @@
+use gloo_net::http::Method;
+use serde::{Deserialize, Serialize};
+use web_sys::Blob;
+
+use crate::supabase::{SupabaseClient, SupabaseError};
+
+/// Objects returned per `list` request.
+const LIST_LIMIT: usize = 100;
+
+/// An object (or folder, which has no `id`) in a Storage bucket listing.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StorageObject {
+    pub name: String,
+    pub id: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadResponse {
+    #[serde(rename = "Key")]
+    key: String,
+}
+
+#[derive(Serialize)]
+struct ListRequest<'a> {
+    prefix: &'a str,
+    limit: usize,
+    offset: usize,
+    #[serde(rename = "sortBy")]
+    sort_by: SortBy,
+}
+
+#[derive(Serialize)]
+struct SortBy {
+    column: &'static str,
+    order: &'static str,
+}
+
+/// Client for one bucket of the Supabase Storage API (`/storage/v1`).
+///
+/// Requests carry the same `apikey` and Bearer token as the data requests,
+/// so bucket policies see the signed-in user.
+#[derive(Clone)]
+pub struct StorageBucket {
+    client: SupabaseClient,
+    bucket: String,
+}
+
+impl SupabaseClient {
+    pub fn storage(&self, bucket: &str) -> StorageBucket {
+        StorageBucket {
+            client: self.clone(),
+            bucket: bucket.to_string(),
+        }
+    }
+}
+
+impl StorageBucket {
+    fn object_url(&self, path: &str) -> String {
+        self.client.endpoint(&format!("/storage/v1/object/{}/{}", self.bucket, encode_path(path)))
+    }
+
+    /// Upload `body` to `path`, replacing what is there. Returns the object path.
+    pub async fn upload(&self, path: &str, body: &Blob, content_type: &str) -> Result<String, SupabaseError> {
+        let url = self.object_url(path);
+        let resp = self.client.send_blob(Method::POST, &url, &[
+            ("Content-Type", content_type),
+            ("x-upsert", "true"),
+        ], body).await?;
+
+        if resp.ok() {
+            let uploaded: UploadResponse = resp.json()?;
+            // The key is prefixed with the bucket name
+            let key = uploaded.key.strip_prefix(&format!("{}/", self.bucket)).unwrap_or(&uploaded.key);
+            Ok(key.to_string())
+        } else {
+            Err(resp.error(&self.bucket))
+        }
+    }
+
+    /// Objects directly under `prefix`, sorted by name.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<StorageObject>, SupabaseError> {
+        let url = self.client.endpoint(&format!("/storage/v1/object/list/{}", self.bucket));
+        let body = serde_json::to_string(&ListRequest {
+            prefix,
+            limit: LIST_LIMIT,
+            offset: 0,
+            sort_by: SortBy { column: "name", order: "asc" },
+        }).map_err(|e| SupabaseError::Request(e.to_string()))?;
+
+        let resp = self.client.send(Method::POST, &url, &[("Content-Type", "application/json")], Some(&body)).await?;
+        if resp.ok() {
+            resp.json()
+        } else {
+            Err(resp.error(&self.bucket))
+        }
+    }
+
+    /// Delete the objects at `paths`.
+    pub async fn remove(&self, paths: &[String]) -> Result<(), SupabaseError> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let url = self.client.endpoint(&format!("/storage/v1/object/{}", self.bucket));
+        let body = serde_json::to_string(&serde_json::json!({ "prefixes": paths }))
+            .map_err(|e| SupabaseError::Request(e.to_string()))?;
+
+        let resp = self.client.send(Method::DELETE, &url, &[("Content-Type", "application/json")], Some(&body)).await?;
+        if resp.ok() {
+            Ok(())
+        } else {
+            Err(resp.error(&self.bucket))
+        }
+    }
+
+    /// URL of an object in a public bucket; no token is needed to read it.
+    pub fn public_url(&self, path: &str) -> String {
+        self.client.endpoint(&format!("/storage/v1/object/public/{}/{}", self.bucket, encode_path(path)))
+    }
+}
+
+/// Where an image uploaded for a project is stored: `projects/<id>/<stamp>-<name>`.
+/// The stamp keeps a second upload of the same file name from replacing the first.
+pub fn project_image_path(project_id: i64, file_name: &str, stamp: u64) -> String {
+    format!("projects/{}/{}-{}", project_id, stamp, sanitize_file_name(file_name))
+}
+
+/// Where a user's avatar is stored: `<user id>/<stamp>-<name>`. The folder is the user id,
+/// which is what the bucket's policies let each user write to.
+pub fn avatar_path(user_id: &str, file_name: &str, stamp: u64) -> String {
+    format!("{}/{}-{}", user_id, stamp, sanitize_file_name(file_name))
+}
+
+/// Lowercase the name and replace anything but letters, digits, `.`, `-` and `_` with `-`.
+fn sanitize_file_name(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    if name.trim_matches(['-', '.']).is_empty() {
+        "image".to_string()
+    } else {
+        name
+    }
+}
+
+/// Percent-encode each segment of an object path, keeping the `/` separators.
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            segment.bytes().map(|b| {
+                if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_' | b'~') {
+                    (b as char).to_string()
+                } else {
+                    format!("%{:02X}", b)
+                }
+            }).collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::supabase::SupabaseConfig;
+
+    #[test]
+    fn image_paths_are_scoped_to_the_project() {
+        assert_eq!(project_image_path(7, "Site Plan (v2).PNG", 1700), "projects/7/1700-site-plan--v2-.png");
+        assert_eq!(project_image_path(7, "..", 1700), "projects/7/1700-image");
+        assert_eq!(avatar_path("8c1e", "Me.JPG", 1700), "8c1e/1700-me.jpg");
+    }
+
+    #[test]
+    fn public_urls_encode_path_segments() {
+        let client = SupabaseClient::new(SupabaseConfig::new("https://demo.supabase.co", "key"));
+        assert_eq!(
+            client.storage("images").public_url("projects/1/ä b.png"),
+            "https://demo.supabase.co/storage/v1/object/public/images/projects/1/%C3%A4%20b.png"
+        );
+    }
+}
@@ -8,7 +8,13 @@ use crate::{ shared::data_state_model::DataState, ui::button::PrimaryButton};
 pub fn InputField<T>(
     data_state: DataState<T>,
     data_handle: impl FnMut() + 'static + Clone + Send, 
-    field_name: String
+    field_name: String,
+    /// Shows the value without letting it be changed or saved.
+    #[prop(default = false)]
+    readonly: bool,
+    /// `text`, `number`, `date`…
+    #[prop(default = "text".to_string())]
+    input_type: String,
 ) -> impl IntoView 
 where
     T: Clone + Send + Sync + 'static,
@@ -22,12 +28,13 @@ where
                     <div class="p-1 rounded-[4px] flex gap-x-1 border border-gray-300">
                                 <input
                                     bind:value=*value
+                                    readonly=readonly
                                     placeholder=format!("Enter {}", field_name)
-                                    type="text" class="p-1 border-none w-full"  />
+                                    type=input_type class="p-1 border-none w-full"  />
                                     {
                                         move || {
                                             let mut save_handler = data_handle.clone();
-                                            if data_state.is_modified.0.get().contains(&field_name) {
+                                            if !readonly && data_state.is_modified.0.get().contains(&field_name) {
                                                 view! { 
                                                       <PrimaryButton 
                                                         on_click=move |_| save_handler()>
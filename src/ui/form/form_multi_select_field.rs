@@ -0,0 +1,123 @@
+use leptos::prelude::*;
+
+use crate::{ shared::data_state_model::DataState, ui::button::PrimaryButton};
+
+/// A list as one `DataState` value: `Computer vision, AI Detection`.
+pub fn join_list(items: &[String]) -> String {
+    items.join(", ")
+}
+
+/// Inverse of `join_list`; blanks and repeats are dropped.
+pub fn split_list(value: &str) -> Vec<String> {
+    let mut items: Vec<String> = vec![];
+    for item in value.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+        if !items.iter().any(|existing| existing == item) {
+            items.push(item.to_string());
+        }
+    }
+    items
+}
+
+/// A `DataState` field holding several values, stored with `join_list`. Known values
+/// are toggled as chips; others can be typed in and added with Enter.
+#[component]
+pub fn MultiSelectField<T>(
+    data_state: DataState<T>,
+    data_handle: impl FnMut() + 'static + Clone + Send,
+    field_name: String,
+    /// Values offered as chips, e.g. those used on other records
+    options: Signal<Vec<String>>,
+    /// Shows the value without letting it be changed or saved.
+    #[prop(default = false)]
+    readonly: bool,
+) -> impl IntoView
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let Some((value, set_value)) = data_state.data.get(&field_name).cloned() else {
+        return view! { <div /> }.into_any();
+    };
+    let (new_item, set_new_item) = signal(String::new());
+
+    let toggle = move |item: String| {
+        let mut items = split_list(&value.get_untracked());
+        match items.iter().position(|existing| *existing == item) {
+            Some(index) => {
+                items.remove(index);
+            }
+            None => items.push(item),
+        }
+        set_value.set(join_list(&items));
+    };
+
+    // Selected values first, then the other known ones
+    let chips = move || {
+        let selected = split_list(&value.get());
+        let mut all = selected.clone();
+        all.extend(options.get().into_iter().filter(|option| !selected.contains(option)));
+        all.into_iter().map(|item| (selected.contains(&item), item)).collect::<Vec<_>>()
+    };
+
+    view! {
+        <div class="p-1 rounded-[4px] flex flex-wrap items-center gap-1 border border-gray-300">
+            {move || chips().into_iter().map(|(is_selected, item)| {
+                let class = if is_selected {
+                    "p-1 px-2 rounded-[6px] cursor-pointer text-sm bg-black text-white"
+                } else {
+                    "p-1 px-2 rounded-[6px] cursor-pointer text-sm bg-gray-100 text-black"
+                };
+                view! {
+                    <button
+                        type="button"
+                        class=class
+                        disabled=readonly
+                        on:click=move |_| toggle(item.clone())
+                    >
+                        {item.clone()}
+                    </button>
+                }
+            }).collect::<Vec<_>>()}
+            {(!readonly).then(|| view! {
+                <input
+                    type="text"
+                    class="p-1 border-none grow text-sm"
+                    placeholder="Add…"
+                    prop:value=new_item
+                    on:input:target=move |ev| set_new_item.set(ev.target().value())
+                    on:keydown=move |ev| {
+                        if ev.key() == "Enter" {
+                            ev.prevent_default();
+                            let item = new_item.get_untracked().trim().to_string();
+                            if !item.is_empty() && !split_list(&value.get_untracked()).contains(&item) {
+                                toggle(item);
+                            }
+                            set_new_item.set(String::new());
+                        }
+                    }
+                />
+            })}
+            {
+                move || {
+                    let mut save_handler = data_handle.clone();
+                    (!readonly && data_state.is_modified.0.get().contains(&field_name)).then(|| view! {
+                        <PrimaryButton on_click=move |_| save_handler()>
+                            "Save"
+                        </PrimaryButton>
+                    })
+                }
+            }
+        </div>
+    }.into_any()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_round_trip_through_text() {
+        let items = split_list(" Computer vision,AI Detection, ,Computer vision ");
+        assert_eq!(items, vec!["Computer vision", "AI Detection"]);
+        assert_eq!(join_list(&items), "Computer vision, AI Detection");
+    }
+}
@@ -0,0 +1,57 @@
+use leptos::prelude::*;
+
+use crate::{ shared::data_state_model::DataState, ui::button::PrimaryButton};
+
+/// A `DataState` field chosen from fixed options, given as `(value, label)` pairs.
+/// The empty value leaves the field unset.
+#[component]
+pub fn SelectField<T>(
+    data_state: DataState<T>,
+    data_handle: impl FnMut() + 'static + Clone + Send,
+    field_name: String,
+    options: Vec<(String, String)>,
+    /// Shows the value without letting it be changed or saved.
+    #[prop(default = false)]
+    readonly: bool,
+    /// Offers the empty value, so a set field can be unset again.
+    #[prop(default = true)]
+    clearable: bool,
+) -> impl IntoView
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let Some(value) = data_state.data.get(&field_name).cloned() else {
+        return view! { <div /> }.into_any();
+    };
+
+    view! {
+        <div class="p-1 rounded-[4px] flex gap-x-1 border border-gray-300">
+            <select
+                class="p-1 border-none w-full bg-white"
+                disabled=readonly
+                on:change:target=move |ev| value.1.set(ev.target().value())
+            >
+                {clearable.then(|| view! {
+                    <option value="" selected=move || value.0.get().is_empty()>"—"</option>
+                })}
+                {options.into_iter().map(|(option, label)| {
+                    let selected = {
+                        let option = option.clone();
+                        move || value.0.get() == option
+                    };
+                    view! { <option value=option selected=selected>{label}</option> }
+                }).collect::<Vec<_>>()}
+            </select>
+            {
+                move || {
+                    let mut save_handler = data_handle.clone();
+                    (!readonly && data_state.is_modified.0.get().contains(&field_name)).then(|| view! {
+                        <PrimaryButton on_click=move |_| save_handler()>
+                            "Save"
+                        </PrimaryButton>
+                    })
+                }
+            }
+        </div>
+    }.into_any()
+}
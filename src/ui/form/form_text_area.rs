@@ -6,7 +6,10 @@ use crate::{ shared::data_state_model::DataState, ui::button::PrimaryButton};
 pub fn FormTextArea<T>(
     data_state: DataState<T>,
     data_handle: impl FnMut() + 'static + Clone + Send, 
-    field_name: String
+    field_name: String,
+    /// Shows the value without letting it be changed or saved.
+    #[prop(default = false)]
+    readonly: bool,
 ) -> impl IntoView 
 where
     T: Clone + Send + Sync + 'static,
@@ -19,6 +22,7 @@ where
                     <div class="p-1 rounded-[4px] w-full h-full flex gap-x-1 border border-gray-300">
                                <textarea
                                         class="p-1 border-none w-full resize-none"
+                                        readonly=readonly
                                         prop:value=move || value.0.get()
                                         on:input:target=move |ev| value.1.set(ev.target().value())
                                     >
@@ -27,7 +31,7 @@ where
                                     {
                                         move || {
                                             let mut save_handler = data_handle.clone();
-                                            if data_state.is_modified.0.get().contains(&field_name) {
+                                            if !readonly && data_state.is_modified.0.get().contains(&field_name) {
                                                 view! { 
                                                     <div class="h-20" >
                                                       <PrimaryButton 
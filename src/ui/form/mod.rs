@@ -1,3 +1,5 @@
 pub mod form_input_field;
+pub mod form_multi_select_field;
+pub mod form_select_field;
 pub mod form_text_area;
 pub mod simple_form_input;
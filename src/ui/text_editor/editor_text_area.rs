@@ -1,8 +1,13 @@
+use futures::future::LocalBoxFuture;
 use leptos::prelude::*;
-use web_sys::HtmlTextAreaElement;
+use leptos::task::spawn_local;
+use web_sys::{ClipboardEvent, DragEvent, File, FileList, HtmlTextAreaElement};
 use wasm_bindgen::JsCast;
 use std::sync::Arc;
 
+/// Uploads an image and resolves to the URL to embed, or to an error message.
+pub type ImageUpload = Arc<dyn Fn(File) -> LocalBoxFuture<'static, Result<String, String>> + Send + Sync>;
+
 // Utility functions for text manipulation
 pub fn insert_text_at_cursor(text: &str, insert: &str, cursor_pos: usize) -> (String, usize) {
     let mut result = String::new();
@@ -49,6 +54,40 @@ pub fn insert_at_line_start(text: &str, cursor_pos: usize, prefix: &str) -> (Str
     (result, new_cursor_pos)
 }
 
+/// Byte index in `text` of a textarea offset, which counts UTF-16 units. Offsets past
+/// the end, or inside a character, land on the end of that character.
+pub fn byte_index(text: &str, utf16_offset: usize) -> usize {
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        if units >= utf16_offset {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+/// Textarea offset, in UTF-16 units, of a byte index in `text`.
+pub fn utf16_offset(text: &str, byte_index: usize) -> usize {
+    text[..byte_index].encode_utf16().count()
+}
+
+/// Markdown embedding an image, alt text taken from the file name without its extension.
+pub fn image_markdown(file_name: &str, url: &str) -> String {
+    let alt = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+    let alt: String = alt.chars().filter(|c| !matches!(c, '[' | ']')).collect();
+    format!("![{}]({})\n", alt, url.replace(' ', "%20"))
+}
+
+/// The images in a dropped or pasted file list.
+pub fn image_files(files: Option<FileList>) -> Vec<File> {
+    let Some(files) = files else { return vec![] };
+    (0..files.length())
+        .filter_map(|i| files.get(i))
+        .filter(|file| file.type_().starts_with("image/"))
+        .collect()
+}
+
 // Context for exposing markdown editing functions to parent components
 #[derive(Clone)]
 pub struct MarkdownEditor {
@@ -59,12 +98,22 @@ pub struct MarkdownEditor {
     pub insert_link: Arc<dyn Fn() + Send + Sync>,
     pub apply_quote: Arc<dyn Fn() + Send + Sync>,
     pub insert_image: Arc<dyn Fn() + Send + Sync>,
+    /// Upload images and insert their markdown at the cursor as each one finishes.
+    pub insert_image_files: Arc<dyn Fn(Vec<File>) + Send + Sync>,
+    /// Whether the editor was given somewhere to upload images to.
+    pub can_upload_images: bool,
+    pub uploading_images: ReadSignal<usize>,
+    pub upload_error: ReadSignal<Option<String>>,
     pub undo: Arc<dyn Fn() + Send + Sync>,
 }
 
 #[component]
 pub fn EditorTextArea(
     value: (ReadSignal<String>, WriteSignal<String>),
+    /// Where images picked, dropped or pasted into the editor are uploaded.
+    /// Without it the editor only inserts an image placeholder.
+    #[prop(default = None)]
+    upload_image: Option<ImageUpload>,
     children: Children
 ) -> impl IntoView {
     let textarea_ref = NodeRef::<leptos::html::Textarea>::new();
@@ -74,6 +123,9 @@ pub fn EditorTextArea(
     let selection_start = RwSignal::new(0usize);
     let selection_end = RwSignal::new(0usize);
     
+    let (uploading_images, set_uploading_images) = signal(0usize);
+    let (upload_error, set_upload_error) = signal(None::<String>);
+    
     // Undo history - store up to 10 previous states
     let history = RwSignal::new(Vec::<(String, usize)>::new()); // (text, cursor_position)
     let history_index = RwSignal::new(0usize);
@@ -294,6 +346,41 @@ pub fn EditorTextArea(
                 set_selection(start + 2, start + 10);
             })
         },
+        insert_image_files: {
+            let upload_image = upload_image.clone();
+            Arc::new(move |files: Vec<File>| {
+                let Some(upload_image) = upload_image.clone() else { return };
+                update_cursor_info();
+                set_upload_error.set(None);
+                for file in files {
+                    let upload_image = upload_image.clone();
+                    let file_name = file.name();
+                    set_uploading_images.update(|n| *n += 1);
+                    spawn_local(async move {
+                        match upload_image(file).await {
+                            Ok(url) => {
+                                // The author may have kept typing, so insert where the cursor is now
+                                let text = value.0.get_untracked();
+                                add_to_history(selection_start.get_untracked());
+                                let start = byte_index(&text, selection_start.get_untracked());
+                                let (new_text, new_cursor) = insert_text_at_cursor(&text, &image_markdown(&file_name, &url), start);
+                                let new_cursor = utf16_offset(&new_text, new_cursor);
+                                value.1.set(new_text);
+                                cursor_position.set(new_cursor);
+                                selection_start.set(new_cursor);
+                                selection_end.set(new_cursor);
+                                set_cursor_position(new_cursor);
+                            }
+                            Err(err) => set_upload_error.set(Some(format!("Could not upload {}: {}", file_name, err))),
+                        }
+                        set_uploading_images.update(|n| *n -= 1);
+                    });
+                }
+            })
+        },
+        can_upload_images: upload_image.is_some(),
+        uploading_images,
+        upload_error,
         undo: {
             let value = value.clone();
             let set_cursor_position = set_cursor_position.clone();
@@ -314,6 +401,8 @@ pub fn EditorTextArea(
         },
     };
     
+    let insert_image_files = markdown_functions.insert_image_files.clone();
+    let can_upload_images = markdown_functions.can_upload_images;
     provide_context(markdown_functions);
     
     view! {
@@ -366,6 +455,33 @@ pub fn EditorTextArea(
                             }
                         }
                     }
+                    on:dragover=move |ev: DragEvent| {
+                        // Allows dropping files onto the textarea
+                        if can_upload_images {
+                            ev.prevent_default();
+                        }
+                    }
+                    on:drop={
+                        let insert_image_files = insert_image_files.clone();
+                        move |ev: DragEvent| {
+                            let files = image_files(ev.data_transfer().and_then(|data| data.files()));
+                            if can_upload_images && !files.is_empty() {
+                                ev.prevent_default();
+                                update_cursor_info();
+                                insert_image_files(files);
+                            }
+                        }
+                    }
+                    on:paste={
+                        let insert_image_files = insert_image_files.clone();
+                        move |ev: ClipboardEvent| {
+                            let files = image_files(ev.clipboard_data().and_then(|data| data.files()));
+                            if can_upload_images && !files.is_empty() {
+                                ev.prevent_default();
+                                insert_image_files(files);
+                            }
+                        }
+                    }
                     on:click=move |_| update_cursor_info()
                     on:keyup=move |_| update_cursor_info()
                     on:select=move |_| update_cursor_info()
@@ -376,3 +492,31 @@ pub fn EditorTextArea(
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_markdown_uses_the_file_stem_as_alt_text() {
+        assert_eq!(
+            image_markdown("site [plan].v2.png", "https://cdn/a b.png"),
+            "![site plan.v2](https://cdn/a%20b.png)\n"
+        );
+    }
+
+    #[test]
+    fn images_are_inserted_after_multibyte_text() {
+        // `selectionStart` after "Café 🔧 " counts the emoji as two units
+        let text = "Café 🔧 Сварка";
+        let start = byte_index(text, 7);
+        assert_eq!(&text[..start], "Café 🔧");
+        let (new_text, new_cursor) = insert_text_at_cursor(text, "![a](u)", start);
+        assert_eq!(new_text, "Café 🔧![a](u) Сварка");
+        assert_eq!(utf16_offset(&new_text, new_cursor), 14);
+
+        // Inside the emoji, or past text shortened during the upload
+        assert_eq!(byte_index(text, 6), text.find('🔧').unwrap() + '🔧'.len_utf8());
+        assert_eq!(byte_index(text, 100), text.len());
+    }
+}
@@ -1,11 +1,14 @@
 use leptos::prelude::*;
 
-use crate::{ shared::data_state_model::{DataState, DataHandler}, ui::text_editor::{editor_text_area::EditorTextArea, toolbar::Toolbar}};
+use crate::{ shared::data_state_model::{DataState, DataHandler}, ui::text_editor::{editor_text_area::{EditorTextArea, ImageUpload}, toolbar::Toolbar}};
 
 #[component]
 pub fn TextEditorView<T, P>(
     data_state: DataState<T, P>,
-    field_name: String
+    field_name: String,
+    /// Enables uploading images picked, dropped or pasted into the editor.
+    #[prop(optional)]
+    upload_image: Option<ImageUpload>,
 ) -> impl IntoView 
 where
     T: Clone + Send + Sync + 'static,
@@ -19,6 +22,7 @@ where
                 view! {
                     <EditorTextArea
                         value=value.clone()
+                        upload_image=upload_image
                     >
                         <Toolbar 
                             data_state=data_state
@@ -2,7 +2,7 @@ use leptos::prelude::*;
 
 use crate::{ 
     shared::data_state_model::{DataState, DataHandler}, 
-    ui::{button::PrimaryButton, text_editor::{editor_text_area::MarkdownEditor, markdown_renderer::MarkdownRenderer}}
+    ui::{button::PrimaryButton, error::ErrorMessage, text_editor::{editor_text_area::{image_files, MarkdownEditor}, markdown_renderer::MarkdownRenderer}}
 };
 
 #[component]
@@ -17,6 +17,7 @@ where
 {
     let markdown_editor = use_context::<MarkdownEditor>().expect("MarkdownEditor context not found");
     let show_preview = RwSignal::new(false);
+    let image_input = NodeRef::<leptos::html::Input>::new();
     
     // Clone for closures
     let data_state_for_preview = data_state.clone();
@@ -113,13 +114,45 @@ where
                         class="flex items-center gap-1 px-2 py-1 text-sm border rounded hover:bg-gray-100"
                         on:click={
                             let insert_image = markdown_editor.insert_image.clone();
+                            let can_upload_images = markdown_editor.can_upload_images;
                             move |_| {
-                                insert_image();
+                                if can_upload_images {
+                                    if let Some(input) = image_input.get() {
+                                        input.click();
+                                    }
+                                } else {
+                                    insert_image();
+                                }
                             }
                         }
                     >
                         "Image"
                     </button>
+                    <input
+                        type="file"
+                        accept="image/*"
+                        multiple
+                        class="hidden"
+                        node_ref=image_input
+                        on:change:target={
+                            let insert_image_files = markdown_editor.insert_image_files.clone();
+                            move |ev| {
+                                let input = ev.target();
+                                insert_image_files(image_files(input.files()));
+                                // Picking the same file again should upload it again
+                                input.set_value("");
+                            }
+                        }
+                    />
+                    
+                    {
+                        let uploading_images = markdown_editor.uploading_images;
+                        move || (uploading_images.get() > 0).then(|| view! {
+                            <span class="self-center text-sm text-gray-500">
+                                {format!("Uploading {} image(s)…", uploading_images.get())}
+                            </span>
+                        })
+                    }
                     
                     <button 
                         class="flex items-center gap-1 px-2 py-1 text-sm border rounded hover:bg-gray-100 bg-yellow-50"
@@ -165,6 +198,13 @@ where
                 }
             </div>
             
+            {
+                let upload_error = markdown_editor.upload_error;
+                move || upload_error.get().map(|message| view! {
+                    <ErrorMessage message=message class="mb-1".to_string() />
+                })
+            }
+            
             // Preview Modal
             {move || {
                 if show_preview.get() {
@@ -0,0 +1,95 @@
+// Account management for the editor's Users screen. Runs with the service role
+// key, which must never be shipped to the browser, and only serves admins whose
+// session verified a second factor (aal2).
+//
+// Deploy: supabase functions deploy admin-users
+import { createClient } from "npm:@supabase/supabase-js@2";
+
+const corsHeaders = {
+  "Access-Control-Allow-Origin": "*",
+  "Access-Control-Allow-Headers": "authorization, apikey, content-type",
+  "Access-Control-Allow-Methods": "POST, OPTIONS",
+};
+
+const ROLES = ["viewer", "editor", "admin"];
+// GoTrue has no permanent ban; a century is close enough
+const DISABLED_FOR = "876000h";
+
+function json(body: unknown, status = 200): Response {
+  return new Response(JSON.stringify(body), {
+    status,
+    headers: { ...corsHeaders, "Content-Type": "application/json" },
+  });
+}
+
+function fail(status: number, message: string): Response {
+  return json({ message }, status);
+}
+
+function claims(token: string): Record<string, unknown> {
+  const payload = token.split(".")[1] ?? "";
+  return JSON.parse(atob(payload.replace(/-/g, "+").replace(/_/g, "/")));
+}
+
+Deno.serve(async (req) => {
+  if (req.method === "OPTIONS") {
+    return new Response(null, { headers: corsHeaders });
+  }
+
+  const admin = createClient(
+    Deno.env.get("SUPABASE_URL")!,
+    Deno.env.get("SUPABASE_SERVICE_ROLE_KEY")!,
+    { auth: { persistSession: false } },
+  );
+
+  // The token is verified by GoTrue; its claims are only read after that
+  const token = (req.headers.get("Authorization") ?? "").replace(/^Bearer /, "");
+  const { data: caller, error: callerError } = await admin.auth.getUser(token);
+  if (callerError || !caller.user) {
+    return fail(401, "Sign in again to manage users");
+  }
+  if (caller.user.app_metadata?.role !== "admin" || claims(token).aal !== "aal2") {
+    return fail(403, "Only admins with two-factor authentication can manage users");
+  }
+
+  const request = await req.json();
+  const role = request.role;
+  if (role !== undefined && !ROLES.includes(role)) {
+    return fail(400, `Unknown role ${role}`);
+  }
+  // An admin locking themselves out could leave the site without one
+  if (request.user_id !== undefined && request.user_id === caller.user.id) {
+    return fail(400, "You cannot change your own account here");
+  }
+
+  switch (request.action) {
+    case "list": {
+      const { data, error } = await admin.auth.admin.listUsers({ perPage: 1000 });
+      return error ? fail(400, error.message) : json(data.users);
+    }
+    case "invite": {
+      const { data, error } = await admin.auth.admin.inviteUserByEmail(request.email, {
+        redirectTo: request.redirect_to ?? undefined,
+      });
+      if (error) return fail(400, error.message);
+      const updated = await admin.auth.admin.updateUserById(data.user.id, {
+        app_metadata: { role },
+      });
+      return updated.error ? fail(400, updated.error.message) : json(updated.data.user);
+    }
+    case "set_role": {
+      const { data, error } = await admin.auth.admin.updateUserById(request.user_id, {
+        app_metadata: { role },
+      });
+      return error ? fail(400, error.message) : json(data.user);
+    }
+    case "set_disabled": {
+      const { data, error } = await admin.auth.admin.updateUserById(request.user_id, {
+        ban_duration: request.disabled ? DISABLED_FOR : "none",
+      });
+      return error ? fail(400, error.message) : json(data.user);
+    }
+    default:
+      return fail(400, `Unknown action ${request.action}`);
+  }
+});
@@ -0,0 +1,31 @@
+-- Link exactly the given areas to a project, in one transaction.
+-- Links to areas that are not listed are deleted, missing ones are inserted and
+-- links that already exist keep their id. Returns the project's links afterwards.
+create or replace function public.set_project_areas(p_project_id bigint, p_area_ids bigint[])
+returns setof public.catalog
+language plpgsql
+security invoker
+as $$
+begin
+  delete from public.catalog
+   where catalog.project_id = p_project_id
+     and not (catalog.area_id = any(p_area_ids));
+
+  insert into public.catalog (project_id, area_id)
+  select distinct p_project_id, ids.area_id
+    from unnest(p_area_ids) as ids(area_id)
+   where not exists (
+     select 1
+       from public.catalog existing
+      where existing.project_id = p_project_id
+        and existing.area_id = ids.area_id
+   );
+
+  return query
+    select * from public.catalog
+     where catalog.project_id = p_project_id
+     order by catalog.id;
+end;
+$$;
+
+grant execute on function public.set_project_areas(bigint, bigint[]) to authenticated;
@@ -0,0 +1,61 @@
+-- Roles are kept in app_metadata.role ('viewer', 'editor' or 'admin'), which only the
+-- service role can change. Everyone may read; editors write; only admins delete
+-- projects and areas. Links and content follow their project, so editors may delete them.
+create or replace function public.app_role()
+returns text
+language sql
+stable
+as $$
+  select coalesce(auth.jwt() -> 'app_metadata' ->> 'role', 'viewer');
+$$;
+
+create or replace function public.is_editor()
+returns boolean
+language sql
+stable
+as $$
+  select public.app_role() in ('editor', 'admin');
+$$;
+
+create or replace function public.is_admin()
+returns boolean
+language sql
+stable
+as $$
+  select public.app_role() = 'admin';
+$$;
+
+do $$
+declare
+  t text;
+begin
+  foreach t in array array['projects', 'areas', 'catalog', 'content'] loop
+    execute format('alter table public.%I enable row level security', t);
+
+    execute format('drop policy if exists "%1$s are readable by everyone" on public.%1$I', t);
+    execute format('create policy "%1$s are readable by everyone" on public.%1$I for select using (true)', t);
+
+    execute format('drop policy if exists "editors insert %1$s" on public.%1$I', t);
+    execute format('create policy "editors insert %1$s" on public.%1$I for insert to authenticated with check (public.is_editor())', t);
+
+    execute format('drop policy if exists "editors update %1$s" on public.%1$I', t);
+    execute format('create policy "editors update %1$s" on public.%1$I for update to authenticated using (public.is_editor()) with check (public.is_editor())', t);
+  end loop;
+end;
+$$;
+
+drop policy if exists "admins delete projects" on public.projects;
+create policy "admins delete projects" on public.projects
+  for delete to authenticated using (public.is_admin());
+
+drop policy if exists "admins delete areas" on public.areas;
+create policy "admins delete areas" on public.areas
+  for delete to authenticated using (public.is_admin());
+
+drop policy if exists "editors delete catalog" on public.catalog;
+create policy "editors delete catalog" on public.catalog
+  for delete to authenticated using (public.is_editor());
+
+drop policy if exists "editors delete content" on public.content;
+create policy "editors delete content" on public.content
+  for delete to authenticated using (public.is_editor());
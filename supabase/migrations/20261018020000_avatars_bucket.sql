@@ -0,0 +1,25 @@
+-- Public bucket of profile pictures. Anyone may read them; each user writes only
+-- under a folder named after their own id, e.g. '<uid>/1700000000-me.png'.
+insert into storage.buckets (id, name, public)
+values ('avatars', 'avatars', true)
+on conflict (id) do nothing;
+
+drop policy if exists "avatars are readable by everyone" on storage.objects;
+create policy "avatars are readable by everyone" on storage.objects
+  for select using (bucket_id = 'avatars');
+
+drop policy if exists "users upload their own avatar" on storage.objects;
+create policy "users upload their own avatar" on storage.objects
+  for insert to authenticated
+  with check (bucket_id = 'avatars' and (storage.foldername(name))[1] = auth.uid()::text);
+
+drop policy if exists "users replace their own avatar" on storage.objects;
+create policy "users replace their own avatar" on storage.objects
+  for update to authenticated
+  using (bucket_id = 'avatars' and (storage.foldername(name))[1] = auth.uid()::text)
+  with check (bucket_id = 'avatars' and (storage.foldername(name))[1] = auth.uid()::text);
+
+drop policy if exists "users delete their own avatar" on storage.objects;
+create policy "users delete their own avatar" on storage.objects
+  for delete to authenticated
+  using (bucket_id = 'avatars' and (storage.foldername(name))[1] = auth.uid()::text);
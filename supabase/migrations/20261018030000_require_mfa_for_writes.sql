@@ -0,0 +1,19 @@
+-- Editors and admins only write from a session that verified a second factor
+-- (aal2), the same level the editor asks for before it opens.
+create or replace function public.is_editor()
+returns boolean
+language sql
+stable
+as $$
+  select public.app_role() in ('editor', 'admin')
+    and coalesce(auth.jwt() ->> 'aal', 'aal1') = 'aal2';
+$$;
+
+create or replace function public.is_admin()
+returns boolean
+language sql
+stable
+as $$
+  select public.app_role() = 'admin'
+    and coalesce(auth.jwt() ->> 'aal', 'aal1') = 'aal2';
+$$;
@@ -0,0 +1,21 @@
+-- Project fields from posts-scheme.json. Statuses and maturities are the values
+-- `ProjectStatus` and `Maturity` serialize to; lists default to empty.
+alter table public.projects
+  add column if not exists technologies text[] not null default '{}',
+  add column if not exists budget bigint,
+  add column if not exists status text
+    check (status in ('planning', 'in-progress', 'on-hold', 'completed')),
+  add column if not exists date_started date,
+  add column if not exists date_ended date,
+  add column if not exists precision integer,
+  add column if not exists tools text[] not null default '{}',
+  add column if not exists client text,
+  add column if not exists industry text,
+  add column if not exists maturity text
+    check (maturity in ('Research', 'POC', 'Product'));
+
+alter table public.projects
+  drop constraint if exists projects_dates_in_order;
+alter table public.projects
+  add constraint projects_dates_in_order
+    check (date_ended is null or date_started is null or date_ended >= date_started);
@@ -0,0 +1,81 @@
+-- Status and maturity workflow. A trigger on projects refuses changes the app's
+-- `ProjectStatus::next` and `Maturity::next` do not allow, and logs the others in
+-- project_transitions. Setting a value for the first time is always allowed.
+create table if not exists public.project_transitions (
+  id bigint generated by default as identity primary key,
+  created_at timestamptz not null default now(),
+  project_id bigint not null references public.projects (id) on delete cascade,
+  field text not null check (field in ('status', 'maturity')),
+  from_value text,
+  to_value text not null
+);
+
+create index if not exists project_transitions_project_id_idx
+  on public.project_transitions (project_id, created_at);
+
+create or replace function public.project_status_allowed(from_status text, to_status text)
+returns boolean
+language sql
+immutable
+as $$
+  select (from_status, to_status) in (
+    ('planning', 'in-progress'), ('planning', 'on-hold'),
+    ('in-progress', 'on-hold'), ('in-progress', 'completed'),
+    ('on-hold', 'in-progress'), ('on-hold', 'completed'),
+    ('completed', 'in-progress')
+  );
+$$;
+
+create or replace function public.project_maturity_allowed(from_maturity text, to_maturity text)
+returns boolean
+language sql
+immutable
+as $$
+  select (from_maturity, to_maturity) in (('Research', 'POC'), ('POC', 'Product'));
+$$;
+
+create or replace function public.log_project_transitions()
+returns trigger
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+  old_status text := case when tg_op = 'UPDATE' then old.status end;
+  old_maturity text := case when tg_op = 'UPDATE' then old.maturity end;
+begin
+  if new.status is distinct from old_status then
+    if new.status is null then
+      raise exception 'A project''s status cannot be cleared';
+    elsif old_status is not null and not public.project_status_allowed(old_status, new.status) then
+      raise exception 'A project cannot go from % to %', old_status, new.status;
+    end if;
+    insert into public.project_transitions (project_id, field, from_value, to_value)
+      values (new.id, 'status', old_status, new.status);
+  end if;
+
+  if new.maturity is distinct from old_maturity then
+    if new.maturity is null then
+      raise exception 'A project''s maturity cannot be cleared';
+    elsif old_maturity is not null and not public.project_maturity_allowed(old_maturity, new.maturity) then
+      raise exception 'Maturity cannot go from % to %', old_maturity, new.maturity;
+    end if;
+    insert into public.project_transitions (project_id, field, from_value, to_value)
+      values (new.id, 'maturity', old_maturity, new.maturity);
+  end if;
+
+  return new;
+end;
+$$;
+
+drop trigger if exists log_project_transitions on public.projects;
+create trigger log_project_transitions
+  after insert or update of status, maturity on public.projects
+  for each row execute function public.log_project_transitions();
+
+-- Everyone may read the log; only the trigger writes it.
+alter table public.project_transitions enable row level security;
+
+drop policy if exists "project_transitions are readable by everyone" on public.project_transitions;
+create policy "project_transitions are readable by everyone" on public.project_transitions
+  for select using (true);
@@ -0,0 +1,24 @@
+-- Public bucket of images embedded in project content, stored under
+-- 'projects/<project id>/<stamp>-<name>'. Anyone may read them; only editors
+-- upload or remove them, as for the rows in role_policies.sql.
+insert into storage.buckets (id, name, public)
+values ('project-images', 'project-images', true)
+on conflict (id) do nothing;
+
+drop policy if exists "project images are readable by everyone" on storage.objects;
+create policy "project images are readable by everyone" on storage.objects
+  for select using (bucket_id = 'project-images');
+
+drop policy if exists "editors upload project images" on storage.objects;
+create policy "editors upload project images" on storage.objects
+  for insert to authenticated
+  with check (
+    bucket_id = 'project-images'
+    and (storage.foldername(name))[1] = 'projects'
+    and public.is_editor()
+  );
+
+drop policy if exists "editors delete project images" on storage.objects;
+create policy "editors delete project images" on storage.objects
+  for delete to authenticated
+  using (bucket_id = 'project-images' and public.is_editor());
@@ -0,0 +1,19 @@
+-- Stream changes to the tables the editor follows through Realtime (`TABLES` in
+-- src/backend/realtime.rs). Full replica identity sends the whole old row with
+-- updates and deletes, so row-level security can be checked against it.
+do $$
+declare
+  t text;
+begin
+  foreach t in array array['projects', 'areas', 'catalog', 'content'] loop
+    execute format('alter table public.%I replica identity full', t);
+
+    if not exists (
+      select 1 from pg_publication_tables
+      where pubname = 'supabase_realtime' and schemaname = 'public' and tablename = t
+    ) then
+      execute format('alter publication supabase_realtime add table public.%I', t);
+    end if;
+  end loop;
+end;
+$$;